@@ -5,26 +5,25 @@ use std::time::{Duration, Instant};
 
 use crate::board::*;
 use crate::color::Color;
+use crate::params::*;
 use crate::stat::Stat;
+use crate::table::*;
 
 pub fn negamax(
     board: &Board,
     color: Color,
     depth: i32,
-    cutoff_to_count: i32,
+    params: &EvalParams,
     stat: &mut Stat) -> Option<(Pos2D, i32)> {
-    let (nm_score, nm_pos) = negamax_worker(board, color, depth*2, cutoff_to_count, std::i32::MIN+1, std::i32::MAX-1, stat);
-    match nm_pos {
-        Some(pos) => Some((pos, nm_score)),
-        None => None
-    }
+    let (nm_score, nm_pos) = negamax_worker(board, color, depth*2, params, i32::MIN+1, i32::MAX-1, stat);
+    nm_pos.map(|pos| (pos, nm_score))
 }
 
 pub fn negamax_worker(
     board: &Board,
     color: Color,
     depth: i32,
-    cutoff_to_count: i32,
+    params: &EvalParams,
     alpha: i32,
     beta: i32,
     stat: &mut Stat) -> (i32, Option<Pos2D>) {
@@ -32,29 +31,49 @@ pub fn negamax_worker(
     let mut alpha = alpha;
     let mut possible_moves: Vec<_> = vec![];
 
-    let mut early_out = depth == 0;
+    let mut early_out = depth == 0 || stat.should_stop();
     if !early_out {
         possible_moves = board.get_available_moves_for(color).collect::<Vec<_>>();
-        if possible_moves.len() == 0 {
+        if possible_moves.is_empty() {
             early_out = true;
         }
     }
 
     if early_out {
-        let score = eval(&board, color, cutoff_to_count);
+        let score = eval(board, color, params);
         return (score, None);
     }
 
-    let mut value = std::i32::MIN+1; // +1 to prevent 'attempt to negate with overflow'
+    // a position searched before, at least as deep, may settle this one; otherwise its
+    // best move is tried first
+    let table = stat.table.clone();
+    if let Some(entry) = table.as_ref().and_then(|table| table.probe(board, color)) {
+        if entry.plies >= depth {
+            let settled = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => entry.score >= beta,
+                Bound::Upper => entry.score <= alpha,
+            };
+            if settled {
+                return (entry.score, entry.best);
+            }
+        }
+        if let Some(index) = entry.best.and_then(|best| possible_moves.iter().position(|&mv| mv == best)) {
+            let best = possible_moves.remove(index);
+            possible_moves.insert(0, best);
+        }
+    }
+
+    let alpha_orig = alpha;
+    let mut value = i32::MIN+1; // +1 to prevent 'attempt to negate with overflow'
     let mut best_move = None;
     for mv in possible_moves {
         let mut child = Board::new_from(board);
         child.place(mv, color);
         stat.nodes_viewed += 1;
 
-        let score: i32;
-        let (nm_score, _) = negamax_worker(&child, color.opposite(), depth-1, cutoff_to_count, -beta, -alpha, stat);
-        score = -nm_score;
+        let (nm_score, _) = negamax_worker(&child, color.opposite(), depth-1, params, -beta, -alpha, stat);
+        let score = -nm_score;
 
         if score > value {
             value = score;
@@ -67,10 +86,150 @@ pub fn negamax_worker(
         }
     }
 
+    // a search that was stopped part way knows nothing for sure
+    if let Some(table) = table.filter(|_| !stat.aborted && depth >= 2) {
+        let bound = if value <= alpha_orig {
+            Bound::Upper
+        } else if value >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        table.store(board, color, Entry { plies: depth, score: value, bound, best: best_move });
+    }
+
     (value, best_move)
 }
 
 
+// Searches deeper and deeper, up to `max_depth`, while the next depth looks like it will
+// finish within `budget`, and gives up on one that runs past it. Returns the result of
+// the deepest search that finished.
+pub fn negamax_timed(
+    board: &Board,
+    color: Color,
+    max_depth: i32,
+    budget: Duration,
+    params: &EvalParams,
+    stat: &mut Stat) -> Option<(Pos2D, i32)> {
+    deepen(board, max_depth, Some(budget), stat, &mut |depth, stat| {
+        negamax(board, color, depth, params, stat).map(|(mv, score)| (score, vec![mv]))
+    })
+}
+
+// Like negamax_timed, but every depth also finds the line of play it expects, to report
+// to the progress of `stat`. Without a budget it goes on up to `max_depth`.
+pub fn principal_variation_timed(
+    board: &Board,
+    color: Color,
+    max_depth: i32,
+    budget: Option<Duration>,
+    params: &EvalParams,
+    stat: &mut Stat) -> Option<(Pos2D, i32)> {
+    deepen(board, max_depth, budget, stat, &mut |depth, stat| {
+        let (score, line) = principal_variation(board, color, depth * 2, params, stat);
+        if line.is_empty() { None } else { Some((score, line)) }
+    })
+}
+
+// A search to one depth, giving the score and the line of play
+type DepthSearch<'a> = dyn FnMut(i32, &mut Stat) -> Option<(i32, Vec<Pos2D>)> + 'a;
+
+// The iterative deepening of negamax_timed, with `search` searching each depth
+fn deepen(
+    board: &Board,
+    max_depth: i32,
+    budget: Option<Duration>,
+    stat: &mut Stat,
+    search: &mut DepthSearch) -> Option<(Pos2D, i32)> {
+    let started = Instant::now();
+    let mut best = None;
+    let mut last = Duration::from_secs(0);
+    for depth in 1..=max_depth {
+        let iteration = Instant::now();
+        let result = search(depth, stat);
+        if stat.aborted {
+            // the search was cut short, so its move can't be trusted
+            break;
+        }
+        let (score, line) = result?;
+        if let Some(progress) = stat.progress.as_mut() {
+            progress(depth, score, &line, stat.nodes_viewed);
+        }
+        best = Some((line[0], score));
+        let budget = match budget {
+            Some(budget) => budget,
+            None => continue,
+        };
+        // the first depth always finishes, so that there is a move to play
+        stat.deadline = Some(started + budget);
+        let took = iteration.elapsed();
+        // each depth takes some times longer than the one before, the guess for the next
+        // one is that it grows as much again
+        let growth = if last.is_zero() { 10.0 } else { (took.as_secs_f64() / last.as_secs_f64()).clamp(4.0, 50.0) };
+        last = took;
+        if depth * 2 >= board.num_empty() as i32 || started.elapsed() + took.mul_f64(growth) > budget {
+            break;
+        }
+    }
+    stat.deadline = None;
+    best
+}
+
+// The score of a search of `plies` plies together with the line of play it expects from
+// both sides. The line ends early where a side has to pass or the game is over.
+pub fn principal_variation(
+    board: &Board,
+    color: Color,
+    plies: i32,
+    params: &EvalParams,
+    stat: &mut Stat) -> (i32, Vec<Pos2D>) {
+    // The search at the root gives the score, the shallower ones after it the rest of the line
+    let mut score = None;
+    let mut line = vec![];
+    let mut board = Board::new_from(board);
+    let mut color = color;
+    for remaining in (1..=plies.max(1)).rev() {
+        let (value, best) = negamax_worker(&board, color, remaining, params, i32::MIN + 1, i32::MAX - 1, stat);
+        score.get_or_insert(value);
+        match best {
+            Some(mv) => {
+                board.place(mv, color);
+                line.push(mv);
+                color = color.opposite();
+            }
+            None => break,
+        }
+    }
+    (score.unwrap(), line)
+}
+
+// The best `count` moves, best first, each with its score and the line of play the search
+// expects after it. Every move gets a search of its own, so this is slower than negamax.
+pub fn multi_pv(
+    board: &Board,
+    color: Color,
+    depth: i32,
+    count: usize,
+    params: &EvalParams,
+    stat: &mut Stat) -> Vec<(i32, Vec<Pos2D>)> {
+    let mut lines: Vec<(i32, Vec<Pos2D>)> = board
+        .get_available_moves_for(color)
+        .map(|mv| {
+            let mut child = Board::new_from(board);
+            child.place(mv, color);
+            stat.nodes_viewed += 1;
+            // one ply less, so that the scores match those of a search from `board`
+            let (score, mut line) = principal_variation(&child, color.opposite(), depth * 2 - 1, params, stat);
+            line.insert(0, mv);
+            (-score, line)
+        })
+        .collect();
+    lines.sort_by_key(|(score, _)| -score);
+    lines.truncate(count);
+    lines
+}
+
 // Timing for initial c4:
 // level 4 -> 4s
 // level 5 -> 168s
@@ -79,13 +238,13 @@ pub fn minimax(
     board: &Board,
     color: Color,
     depth: i32,
-    cutoff_to_count: i32,
+    params: &EvalParams,
     stat: &mut Stat) -> Option<(Pos2D, i32)> {
     if depth == 0 {
         panic!("depth cannot be 0!");
     }
     let possible_moves = board.get_available_moves_for(color);
-    let mut max_score = std::i32::MIN;
+    let mut max_score = i32::MIN;
     let mut best_move: Option<(Pos2D, i32)> = None;
 
     for mv in possible_moves {
@@ -95,24 +254,22 @@ pub fn minimax(
 
         let possible_oppo_moves = board_copy.get_available_moves_for(color.opposite());
 
-        let mut min_score = std::i32::MAX;
+        let mut min_score = i32::MAX;
         let mut best_oppo_move: Option<(Pos2D, i32)> = None;
         for mv_oppo in possible_oppo_moves {
             let mut board_copy2 = Board::new_from(&board_copy);
             board_copy2.place(mv_oppo, color.opposite());
             stat.nodes_viewed += 1;
 
-            let oppo_score: i32;
-
-            if depth > 1 {
-                let best2 = minimax(&board_copy2, color, depth-1, cutoff_to_count, stat);
-                oppo_score = match best2 {
+            let oppo_score = if depth > 1 {
+                let best2 = minimax(&board_copy2, color, depth-1, params, stat);
+                match best2 {
                     Some(s) => s.1,
-                    None => eval(&board_copy2, color, cutoff_to_count),
-                };
+                    None => eval(&board_copy2, color, params),
+                }
             } else {
-                oppo_score = eval(&board_copy2, color, cutoff_to_count);
-            }
+                eval(&board_copy2, color, params)
+            };
 
             // Alpha-beta pruning
             if oppo_score <= max_score {
@@ -127,7 +284,7 @@ pub fn minimax(
 
         let score = match best_oppo_move {
             Some((_, s)) => s,
-            None => eval(&board_copy, color, cutoff_to_count),
+            None => eval(&board_copy, color, params),
         };
 
         if score > max_score {
@@ -145,137 +302,143 @@ pub fn caclulate_score(board: &Board) -> (usize, usize) {
     )
 }
 
-pub fn eval_corners(board: &Board, color: Color) -> i32 {
-    let mut score:i32 = 0;
-    let corner0 = board.get_at(Pos2D::new(0,0));
-    if corner0 == color {
-        score += 100;
-        if board.get_at(Pos2D::new(1,0)) == color {
-            score += 50;
-            if board.get_at(Pos2D::new(2,0)) == color {
-                score += 50;
-                if board.get_at(Pos2D::new(3,0)) == color {
-                    score += 50;
-                }
-            }
-        }
-        if board.get_at(Pos2D::new(0,1)) == color {
-            score += 50;
-            if board.get_at(Pos2D::new(0,2)) == color {
-                score += 50;
-                if board.get_at(Pos2D::new(0,3)) == color {
-                    score += 50;
-                }
-            }
-        }
-    }
-    else if corner0 == Color::Empty {
-        if board.get_at(Pos2D::new(1,1)) == color {
-            score -= 100;
-        }
-    }
+// Each corner together with the directions that lead from it along its two edges
+fn corners(size: usize) -> [(usize, usize, i32, i32); 4] {
+    let last = size - 1;
+    [
+        (0, 0, 1, 1),
+        (last, 0, -1, 1),
+        (0, last, 1, -1),
+        (last, last, -1, -1),
+    ]
+}
 
-    let corner1 = board.get_at(Pos2D::new(7,0));
-    if corner1 == color {
-        score += 100;
-        if board.get_at(Pos2D::new(6,0)) == color {
-            score += 50;
-            if board.get_at(Pos2D::new(5,0)) == color {
-                score += 50;
-                if board.get_at(Pos2D::new(4,0)) == color {
-                    score += 50;
-                }
-            }
+#[derive(Default, Debug)]
+pub struct CornerFeatures {
+    pub corners: i32,
+    // discs in an unbroken run of up to three along an edge from an owned corner
+    pub edge_runs: i32,
+    // X-squares (diagonally next to a corner) and C-squares (next to a corner on the edge)
+    // occupied while the corner is still empty
+    pub x_squares: i32,
+    pub c_squares: i32,
+}
+
+pub fn corner_features(board: &Board, color: Color) -> CornerFeatures {
+    let mut features = CornerFeatures::default();
+    for &(ci, cj, di, dj) in &corners(board.size()) {
+        let at = |ki: i32, kj: i32| {
+            board.get_at(Pos2D::new((ci as i32 + ki * di) as usize, (cj as i32 + kj * dj) as usize))
+        };
+        if board.is_blocked(Pos2D::new(ci, cj)) {
+            // nobody can take this corner, so there is nothing to win or lose around it
+            continue;
         }
-        if board.get_at(Pos2D::new(7,1)) == color {
-            score += 50;
-            if board.get_at(Pos2D::new(7,2)) == color {
-                score += 50;
-                if board.get_at(Pos2D::new(7,3)) == color {
-                    score += 50;
-                }
+        let corner = at(0, 0);
+        if corner == color {
+            features.corners += 1;
+            features.edge_runs += (1..4).take_while(|k| at(*k, 0) == color).count() as i32;
+            features.edge_runs += (1..4).take_while(|k| at(0, *k) == color).count() as i32;
+        } else if corner == Color::Empty {
+            if at(1, 1) == color {
+                features.x_squares += 1;
             }
-        }
-    }
-    else if corner1 == Color::Empty {
-        if board.get_at(Pos2D::new(6,1)) == color {
-            score -= 100;
-        }
-    }
-
-    let corner2 = board.get_at(Pos2D::new(0,7));
-    if corner2 == color {
-        score += 100;
-        if board.get_at(Pos2D::new(1,7)) == color {
-            score += 50;
-            if board.get_at(Pos2D::new(2,7)) == color {
-                score += 50;
-                if board.get_at(Pos2D::new(3,7)) == color {
-                    score += 50;
-                }
+            if at(1, 0) == color {
+                features.c_squares += 1;
             }
-        }
-        if board.get_at(Pos2D::new(0,6)) == color {
-            score += 50;
-            if board.get_at(Pos2D::new(0,5)) == color {
-                score += 50;
-                if board.get_at(Pos2D::new(0,4)) == color {
-                    score += 50;
-                }
+            if at(0, 1) == color {
+                features.c_squares += 1;
             }
         }
     }
-    else if corner2 == Color::Empty {
-        if board.get_at(Pos2D::new(1,6)) == color {
-            score -= 100;
-        }
-    }
+    features
+}
 
-    let corner3 = board.get_at(Pos2D::new(7,7));
-    if corner3 == color {
-        score += 100;
-        if board.get_at(Pos2D::new(6,7)) == color {
-            score += 50;
-            if board.get_at(Pos2D::new(5,7)) == color {
-                score += 50;
-                if board.get_at(Pos2D::new(4,7)) == color {
-                    score += 50;
-                }
+pub fn eval_corners(board: &Board, color: Color, weights: &Weights) -> i32 {
+    let features = corner_features(board, color);
+    features.corners * weights.corner
+        + features.edge_runs * weights.edge_run
+        + features.x_squares * weights.x_square
+        + features.c_squares * weights.c_square
+}
+
+// Discs of the given color that touch at least one empty cell
+pub fn count_frontier(board: &Board, color: Color) -> i32 {
+    let size = board.size();
+    let mut count = 0;
+    for i in 0..size {
+        for j in 0..size {
+            if board.get_at(Pos2D::new(i, j)) != color {
+                continue;
             }
-        }
-        if board.get_at(Pos2D::new(7,6)) == color {
-            score += 50;
-            if board.get_at(Pos2D::new(7,5)) == color {
-                score += 50;
-                if board.get_at(Pos2D::new(7,4)) == color {
-                    score += 50;
-                }
+            let touches_empty = (-1..=1).any(|di: i32| {
+                (-1..=1).any(|dj: i32| {
+                    let ni = (i as i32 + di) as usize;
+                    let nj = (j as i32 + dj) as usize;
+                    ni < size && nj < size && board.is_free(Pos2D::new(ni, nj))
+                })
+            });
+            if touches_empty {
+                count += 1;
             }
         }
     }
-    else if corner3 == Color::Empty {
-        if board.get_at(Pos2D::new(6,6)) == color {
-            score -= 100;
-        }
-    }
-    score
+    count
+}
+
+// +1 if the side to move can expect to get the last move, -1 otherwise
+pub fn parity(board: &Board) -> i32 {
+    if board.num_empty() % 2 == 1 { 1 } else { -1 }
+}
+
+// The number of discs an 8x8 board with as many empty cells would have. The phases in
+// EvalParams are given in these terms, so that they carry over to other board sizes.
+pub fn game_phase(board: &Board) -> i32 {
+    64 - board.num_empty() as i32
+}
+
+// The heuristic terms of eval as differences between `color` and its opponent, in the
+// order of Weights::to_array, so that eval is their dot product with the weights
+pub fn eval_features(board: &Board, color: Color) -> [i32; 7] {
+    let opposite = color.opposite();
+    let (mobility, oppo_mobility) = board.count_available_moves(color, opposite);
+    let own = corner_features(board, color);
+    let oppo = corner_features(board, opposite);
+    let sign = board.variant().disc_sign();
+    [
+        mobility - oppo_mobility,
+        count_frontier(board, color) - count_frontier(board, opposite),
+        (own.corners - oppo.corners) * sign,
+        (own.x_squares - oppo.x_squares) * sign,
+        (own.c_squares - oppo.c_squares) * sign,
+        (own.edge_runs - oppo.edge_runs) * sign,
+        parity(board),
+    ]
 }
 
-pub fn eval(board: &Board, color: Color, cutoff_to_count: i32) -> i32 {
-    let occupied = board.num_occupied();
+pub fn eval(board: &Board, color: Color, params: &EvalParams) -> i32 {
+    let occupied = game_phase(board);
     let mut score: i32;
-    if occupied < cutoff_to_count as usize {
+    if occupied < params.count_from {
 
         // What matters at this stage is stable cells, plus minimizing number of opponent moves
 
-        score =  board.get_available_moves_for(color).count() as i32;
-        score -= board.get_available_moves_for(color.opposite()).count() as i32;
+        let weights = params.weights_for(occupied);
+        let (mobility, oppo_mobility) = board.count_available_moves(color, color.opposite());
+        score = (mobility - oppo_mobility) * weights.mobility;
 
-        score += eval_corners(board, color);
-        score -= eval_corners(board, color.opposite());
+        // Corners and the edges next to them hold on to discs, which in anti-reversi
+        // is what a player wants to avoid
+        let corners = eval_corners(board, color, weights) - eval_corners(board, color.opposite(), weights);
+        score += corners * board.variant().disc_sign();
+
+        if weights.frontier != 0 {
+            score += (count_frontier(board, color) - count_frontier(board, color.opposite())) * weights.frontier;
+        }
+        score += parity(board) * weights.parity;
 
     } else {
-        score = board.num_of_color(color) as i32 - board.num_of_color(color.opposite()) as i32;
+        score = board.disc_lead(color);
     }
     score
 }
@@ -1,5 +1,3 @@
-use all_asserts;
-
 #[cfg(test)]
 mod test {
 
@@ -7,6 +5,7 @@ mod test {
     use crate::color::*;
     use crate::stat::Stat;
     use crate::analysis::*;
+    use crate::params::EvalParams;
 
     fn get_starting_board_precorner() -> Board {
         let mut b = Board::new();
@@ -21,16 +20,16 @@ mod test {
 
         let b = get_starting_board_precorner();
         let moves = b.get_available_moves_for(Color::White).collect::<Vec<_>>();
-        assert_eq!(moves.iter().count(), 1);
+        assert_eq!(moves.len(), 1);
 
         let mut stat = Stat::new();
-        let (pos, score) = negamax(&b, Color::White, 1, 54, &mut stat).unwrap();
+        let (pos, score) = negamax(&b, Color::White, 1, &EvalParams::counting_from(54), &mut stat).unwrap();
 
         assert_eq!(pos, Pos2D::new(1,1));
         all_asserts::assert_lt!(score, 0);
 
         // Now apply final disk count
-        let (pos, score) = negamax(&b, Color::White, 1, 1, &mut stat).unwrap();
+        let (pos, score) = negamax(&b, Color::White, 1, &EvalParams::counting_from(1), &mut stat).unwrap();
 
         assert_eq!(pos, Pos2D::new(1,1));
         assert_eq!(score, 4);
@@ -48,10 +47,10 @@ mod test {
 
         let b = get_starting_board_corner();
         let moves = b.get_available_moves_for(Color::White).collect::<Vec<_>>();
-        assert_eq!(moves.iter().count(), 1);
+        assert_eq!(moves.len(), 1);
 
         let mut stat = Stat::new();
-        let (pos, score) = negamax(&b, Color::White, 1, 54, &mut stat).unwrap();
+        let (pos, score) = negamax(&b, Color::White, 1, &EvalParams::counting_from(54), &mut stat).unwrap();
 
         assert_eq!(pos, Pos2D::new(7,7));
         all_asserts::assert_gt!(score, 100); // one corner is taken, so at least 100
@@ -72,12 +71,40 @@ mod test {
 
         let b = get_starting_board_corner2();
         let moves = b.get_available_moves_for(Color::White).collect::<Vec<_>>();
-        assert_eq!(moves.iter().count(), 2);
+        assert_eq!(moves.len(), 2);
 
         let mut stat = Stat::new();
-        let (pos, score) = negamax(&b, Color::White, 1, 54, &mut stat).unwrap();
+        let (pos, score) = negamax(&b, Color::White, 1, &EvalParams::counting_from(54), &mut stat).unwrap();
 
         assert_eq!(pos, Pos2D::new(7,7));
         all_asserts::assert_gt!(score, 0); // must be slightly better
     }
+
+    #[test]
+    fn test_timed_search() {
+        let b = get_starting_board_corner2();
+        let params = EvalParams::counting_from(54);
+
+        // with no time at all it still finishes the first depth
+        let mut stat = Stat::new();
+        let (pos, _) = negamax_timed(&b, Color::White, 6, std::time::Duration::from_secs(0), &params, &mut stat).unwrap();
+        assert_eq!(pos, Pos2D::new(7,7));
+        assert_eq!(Some((pos, negamax(&b, Color::White, 1, &params, &mut Stat::new()).unwrap().1)),
+            negamax_timed(&b, Color::White, 1, std::time::Duration::from_secs(60), &params, &mut stat));
+    }
+
+    #[test]
+    fn test_multi_pv() {
+        let b = Board::new_start();
+        let params = EvalParams::default();
+        let lines = multi_pv(&b, Color::Black, 2, 3, &params, &mut Stat::new());
+        assert_eq!(lines.len(), 3);
+        assert!(lines.windows(2).all(|w| w[0].0 >= w[1].0));
+        // the first line agrees with the plain search
+        let (pos, score) = negamax(&b, Color::Black, 2, &params, &mut Stat::new()).unwrap();
+        assert_eq!(lines[0].0, score);
+        assert!(b.can_place(lines[0].1[0], Color::Black));
+        assert_eq!(lines.iter().find(|(_, line)| line[0] == pos).unwrap().0, score);
+        assert_eq!(lines[0].1.len(), 4);
+    }
 }
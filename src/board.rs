@@ -1,11 +1,15 @@
 ﻿#![allow(dead_code)]
 
 use crate::color::Color;
+use crate::render::{Highlights, Renderer};
 use crate::stat::*;
 use crate::transcript::*;
 use log::{error, info, set_max_level, trace, warn};
+use serde::{Serialize, Serializer};
 use std::fmt;
 use std::mem;
+use std::str::FromStr;
+use std::sync::OnceLock;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Pos2D {
@@ -17,6 +21,29 @@ impl Pos2D {
     pub fn new(ii: usize, jj: usize) -> Pos2D {
         Pos2D { i: ii, j: jj }
     }
+
+    // The notation used in transcripts and at the prompt: column letter followed
+    // by a zero-based row, e.g. "c4". Whether the cell is on the board is up to the caller.
+    pub fn parse(s: &str) -> Option<Pos2D> {
+        let bytes = s.trim().to_ascii_lowercase().into_bytes();
+        if bytes.len() != 2 || !(b'a'..=b'j').contains(&bytes[0]) || !bytes[1].is_ascii_digit() {
+            return None;
+        }
+        Some(Pos2D::new((bytes[0] - b'a') as usize, (bytes[1] - b'0') as usize))
+    }
+
+    // Standard notation, as used by FFO and most other programs: column letter
+    // followed by a one-based row, e.g. "a1" for the top left corner
+    pub fn from_standard(s: &str) -> Option<Pos2D> {
+        let s = s.trim().to_ascii_lowercase();
+        let column = s.bytes().next().filter(|c| (b'a'..=b'j').contains(c))?;
+        let row: usize = s[1..].parse().ok().filter(|r| (1..=MAX_SIZE).contains(r))?;
+        Some(Pos2D::new((column - b'a') as usize, row - 1))
+    }
+
+    pub fn to_standard(self) -> String {
+        format!("{}{}", ((self.i as u8) + 97) as char, self.j + 1)
+    }
 }
 
 impl fmt::Display for Pos2D {
@@ -25,66 +52,211 @@ impl fmt::Display for Pos2D {
     }
 }
 
+// Serialized the way it is displayed, e.g. "c4"
+impl Serialize for Pos2D {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
 impl PartialEq for Pos2D {
     fn eq(&self, other: &Self) -> bool {
         (self.i == other.i) && (self.j == other.j)
     }
 }
 
+// Boards are square with an even number of cells per side, from 4 to MAX_SIZE
+pub const MAX_SIZE: usize = 10;
+
+pub fn is_valid_size(size: usize) -> bool {
+    (4..=MAX_SIZE).contains(&size) && size.is_multiple_of(2)
+}
+
+// The order in which moves are tried, one list per board size. Moves that tend to be
+// better are considered first, which helps alpha-beta: the corners, then the edge cells
+// two away from a corner, then everything else except the X-squares, which come last.
+// The center cells are in it too: a position that was set up may have them empty.
+static MOVE_ORDERS: [OnceLock<Vec<Pos2D>>; MAX_SIZE / 2 - 1] = [const { OnceLock::new() }; MAX_SIZE / 2 - 1];
+
+fn move_order(size: usize) -> &'static [Pos2D] {
+    MOVE_ORDERS[size / 2 - 2].get_or_init(|| {
+        let last = size - 1;
+        let from_edge = |k: usize| k.min(last - k);
+        let mut cells: Vec<Pos2D> = (0..size * size).map(|index| Pos2D::new(index / size, index % size)).collect();
+        cells.sort_by_key(|p| match (from_edge(p.i), from_edge(p.j)) {
+            (0, 0) => 0,
+            (0, 2) | (2, 0) => 1,
+            (1, 1) => 3,
+            _ => 2,
+        });
+        cells
+    })
+}
+
+// The rule that decides who wins at the end of the game
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Variant {
+    // the side with more discs wins
+    #[default]
+    Normal,
+    // anti-reversi: the side with fewer discs wins
+    Anti,
+}
+
+impl Variant {
+    // +1 if discs are worth having, -1 if they are a burden
+    pub fn disc_sign(self) -> i32 {
+        match self {
+            Variant::Normal => 1,
+            Variant::Anti => -1,
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Variant::Normal => write!(f, "normal"),
+            Variant::Anti => write!(f, "anti"),
+        }
+    }
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Variant, String> {
+        match s.trim().to_lowercase().as_str() {
+            "normal" => Ok(Variant::Normal),
+            "anti" | "misere" => Ok(Variant::Anti),
+            other => Err(format!("unknown variant '{}'", other)),
+        }
+    }
+}
+
 //#[derive(Clone)]
 pub struct Board {
-    board_data: [Color; 64],
+    board_data: [Color; MAX_SIZE * MAX_SIZE],
+    size: usize,
+    variant: Variant,
+    // cells where no disc can ever be, one bit per cell in the order of cells(). A blocked
+    // cell reads as empty, so lines of discs stop at it.
+    blocked: u128,
 }
 
 impl Board {
     pub fn new() -> Board {
+        Board::new_sized(8)
+    }
+
+    // An empty board with size x size cells
+    pub fn new_sized(size: usize) -> Board {
+        assert!(is_valid_size(size), "unsupported board size {}", size);
         Board {
-            board_data: [Color::Empty; 64],
+            board_data: [Color::Empty; MAX_SIZE * MAX_SIZE],
+            size,
+            variant: Variant::Normal,
+            blocked: 0,
         }
     }
 
+    // The standard four-disc starting position
+    pub fn new_start() -> Board {
+        Board::new_start_sized(8)
+    }
+
+    pub fn new_start_sized(size: usize) -> Board {
+        let mut board = Board::new_sized(size);
+        let h = size / 2;
+        board.set_at(Pos2D::new(h - 1, h - 1), Color::Black);
+        board.set_at(Pos2D::new(h - 1, h), Color::White);
+        board.set_at(Pos2D::new(h, h - 1), Color::White);
+        board.set_at(Pos2D::new(h, h), Color::Black);
+        board
+    }
+
     pub fn new_from(other: &Board) -> Board {
         Board {
-            board_data: other.board_data.clone()
+            board_data: other.board_data,
+            size: other.size,
+            variant: other.variant,
+            blocked: other.blocked,
+        }
+    }
+
+    pub fn is_blocked(&self, p: Pos2D) -> bool {
+        self.blocked & (1 << (p.j * self.size + p.i)) != 0
+    }
+
+    // Blocking a cell removes any disc from it
+    pub fn set_blocked(&mut self, p: Pos2D, blocked: bool) {
+        let bit = 1 << (p.j * self.size + p.i);
+        if blocked {
+            self.set_at(p, Color::Empty);
+            self.blocked |= bit;
+        } else {
+            self.blocked &= !bit;
         }
     }
 
+    pub fn blocked_mask(&self) -> u128 {
+        self.blocked
+    }
+
+    // An empty cell a disc could still go to
+    pub fn is_free(&self, p: Pos2D) -> bool {
+        self.get_at(p) == Color::Empty && !self.is_blocked(p)
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    pub fn set_variant(&mut self, variant: Variant) {
+        self.variant = variant
+    }
+
+    // How many discs `color` is ahead of its opponent, counted so that being ahead is
+    // good for it: in anti-reversi, having fewer discs is being ahead
+    pub fn disc_lead(&self, color: Color) -> i32 {
+        let own = self.num_of_color(color) as i32;
+        let opponent = self.num_of_color(color.opposite()) as i32;
+        (own - opponent) * self.variant.disc_sign()
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn num_cells(&self) -> usize {
+        self.size * self.size
+    }
+
+    pub fn contains(&self, p: Pos2D) -> bool {
+        p.i < self.size && p.j < self.size
+    }
+
     pub fn num_occupied(&self) -> usize {
-        64 - self.num_of_color(Color::Empty)
-    }
-
-    const POSITIONS_NORM : [usize; 64] = [ 0, 1, 2, 3, 4, 5, 6, 7,
-                                           8, 9,10,11,12,13,14,15,
-                                          16,17,18,19,20,21,22,23,
-                                          24,25,26,27,28,29,30,31,
-                                          32,33,34,35,36,37,38,39,
-                                          40,41,42,43,44,45,46,47,
-                                          48,49,50,51,52,53,54,55,
-                                          56,57,58,59,60,61,62,63 ];
-
-    // This order is optimized for alpha-beta: moves that tend to be better
-    // (such as corners) are considered first
-    const POSITIONS_OPT : [usize; 60] =  [    0, 7,56,63, 2, 5,16,23,
-                                             40,47,58,61, 1, 3, 4, 6,
-                                              8,10,11,12,13,15,17,18,
-                                             19,20,21,22,24,25,26,29,
-                                             30,31,32,33,34,37,38,39,
-                                             41,42,43,44,45,46,48,50,
-                                             51,52,53,55,57,59,60,62,
-                                             9,14,49,54];
+        self.num_of_color(Color::Black) + self.num_of_color(Color::White)
+    }
+
+    // Empty cells that are not blocked
+    pub fn num_empty(&self) -> usize {
+        self.num_of_color(Color::Empty) - self.blocked.count_ones() as usize
+    }
 
     fn get_possibly_available_positions(&self) -> impl Iterator<Item = Pos2D> {
-        Board::POSITIONS_OPT.iter()
-            .map(|i| Pos2D::new(i / 8, i % 8))
+        move_order(self.size).iter().copied()
     }
 
-    fn get_all_positions(&self) -> impl Iterator<Item = Pos2D> {
-        Board::POSITIONS_NORM.iter()
-            .map(|i| Pos2D::new(i / 8, i % 8))
+    // Every cell, row by row
+    pub fn cells(&self) -> impl Iterator<Item = Pos2D> {
+        let size = self.size;
+        (0..size * size).map(move |index| Pos2D::new(index % size, index / size))
     }
 
     pub fn num_of_color(&self, color: Color) -> usize {
-        self.get_all_positions()
+        self.cells()
             .filter(|p| self.get_at(*p) == color)
             .count()
     }
@@ -117,22 +289,7 @@ impl Board {
     ];
 
     pub fn print(&self) {
-        println!("      A     B     C     D     E     F     G     H");
-        //println!("      0     1     2     3     4     5     6     7");
-        println!("   ┌─────┬─────┬─────┬─────┬─────┬─────┬─────┬─────┐");
-        for i in 0..8 {
-            print!("{}  │", i);
-            for j in 0..8 {
-                print!("  {}  │", self.get_at(Pos2D::new(j, i)));
-            }
-            println!("");
-            if i == 7 {
-                println!("   └─────┴─────┴─────┴─────┴─────┴─────┴─────┴─────┘");
-            }
-            else {
-                println!("   ├─────┼─────┼─────┼─────┼─────┼─────┼─────┼─────┤");
-            }
-        }
+        Renderer::default().print(self, &Highlights::default());
     }
 
     fn char_to_index(c: char) -> usize {
@@ -148,11 +305,11 @@ impl Board {
     }
 
     pub fn set_at(&mut self, p: Pos2D, color: Color) {
-        self.set_at_pos_internal(p.j * 8 + p.i, color)
+        self.set_at_pos_internal(p.j * self.size + p.i, color)
     }
 
     pub fn get_at(&self, p: Pos2D) -> Color {
-        self.get_at_pos_internal(p.j * 8 + p.i)
+        self.get_at_pos_internal(p.j * self.size + p.i)
     }
 
     fn get_at_pos_internal(&self, index: usize) -> Color {
@@ -174,6 +331,9 @@ impl Board {
         if self.get_at(position) != Color::Empty {
              panic!("Cannot place to non-empty cell");
         }
+        if self.is_blocked(position) {
+             panic!("Cannot place to blocked cell");
+        }
         let mut total_flipped = 0;
         let opposite = color.opposite();
         for direction in &Board::DIRS {
@@ -182,7 +342,7 @@ impl Board {
             loop {
                 new_position.i = (new_position.i as i32 + direction[0]) as usize;
                 new_position.j = (new_position.j as i32 + direction[1]) as usize;
-                if new_position.i < 8 && new_position.j < 8 {
+                if new_position.i < self.size && new_position.j < self.size {
                     let color_at_this_position = self.get_at(new_position);
                     if color_at_this_position == opposite {
                         flipped += 1;
@@ -224,7 +384,7 @@ impl Board {
     }
 
     pub fn can_place(&self, position: Pos2D, color: Color) -> bool {
-        if self.get_at(position) != Color::Empty {
+        if !self.contains(position) || !self.is_free(position) {
             return false;
         }
         let opposite = color.opposite();
@@ -234,7 +394,7 @@ impl Board {
             loop {
                 new_position.i = (new_position.i as i32 + direction[0]) as usize;
                 new_position.j = (new_position.j as i32 + direction[1]) as usize;
-                if new_position.i < 8 && new_position.j < 8 {
+                if new_position.i < self.size && new_position.j < self.size {
                     let color_at_this_position = self.get_at(new_position);
                     if color_at_this_position == opposite {
                         flipped += 1;
@@ -261,3 +421,50 @@ impl Board {
         last_mover
     }
 }
+
+// The 64-character board notation used by FFO and NBoard: one character per cell,
+// row by row starting at a1, X for Black, O for White and - for an empty cell.
+// Other board sizes have as many characters as cells, and blocked cells are #.
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for p in self.cells() {
+            let c = match self.get_at(p) {
+                _ if self.is_blocked(p) => '#',
+                Color::Black => 'X',
+                Color::White => 'O',
+                Color::Empty => '-',
+            };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Board {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Board, String> {
+        let cells = s.trim();
+        let count = cells.chars().count();
+        let size = match (4..=MAX_SIZE).find(|size| size * size == count) {
+            Some(size) if is_valid_size(size) => size,
+            _ => return Err(format!("expected 64 cells, or 16, 36 or 100 for other sizes, got {}", count)),
+        };
+        let mut board = Board::new_sized(size);
+        for (index, c) in cells.chars().enumerate() {
+            let p = Pos2D::new(index % size, index / size);
+            let color = match c {
+                'X' | 'x' | '*' => Color::Black,
+                'O' | 'o' => Color::White,
+                '-' | '.' => Color::Empty,
+                '#' => {
+                    board.set_blocked(p, true);
+                    continue;
+                }
+                _ => return Err(format!("unexpected '{}' in board", c)),
+            };
+            board.set_at(p, color);
+        }
+        Ok(board)
+    }
+}
@@ -35,9 +35,9 @@ mod test {
         let b = get_starting_board();
         let mut moves = b.get_available_moves_for(Color::Black).collect::<Vec<_>>();
 
-        assert_eq!(moves.iter().count(), 4);
+        assert_eq!(moves.len(), 4);
 
-        moves.sort_by(|a, b| (a.i*100+a.j).cmp(&(b.i*100+b.j)));
+        moves.sort_by_key(|a| a.i*100+a.j);
 
         assert_eq!(moves[0], Pos2D::new(2,4));
         assert_eq!(moves[1], Pos2D::new(3,5));
@@ -51,13 +51,98 @@ mod test {
         let b = get_starting_board();
         let mut moves = b.get_available_moves_for(Color::White).collect::<Vec<_>>();
 
-        assert_eq!(moves.iter().count(), 4);
+        assert_eq!(moves.len(), 4);
 
-        moves.sort_by(|a, b| (a.i*100+a.j).cmp(&(b.i*100+b.j)));
+        moves.sort_by_key(|a| a.i*100+a.j);
 
         assert_eq!(moves[0], Pos2D::new(2,3));
         assert_eq!(moves[1], Pos2D::new(3,2));
         assert_eq!(moves[2], Pos2D::new(4,5));
         assert_eq!(moves[3], Pos2D::new(5,4));
     }
+
+    #[test]
+    fn test_other_board_sizes() {
+        for &size in &[4, 6, 10] {
+            let b = Board::new_start_sized(size);
+            assert_eq!(b.num_cells(), size * size);
+            assert_eq!(b.num_occupied(), 4);
+            assert_eq!(b.get_available_moves_for(Color::Black).count(), 4);
+            assert!(b.contains(Pos2D::new(size - 1, size - 1)));
+            assert!(!b.contains(Pos2D::new(size, 0)));
+            assert!(!b.can_place(Pos2D::new(0, size), Color::Black));
+
+            let cells = b.to_string();
+            assert_eq!(cells.len(), size * size);
+            assert_eq!(cells.parse::<Board>().unwrap().size(), size);
+        }
+        assert!("-".repeat(49).parse::<Board>().is_err());
+        assert_eq!(Pos2D::from_standard("j10"), Some(Pos2D::new(9, 9)));
+        assert_eq!(Pos2D::from_standard("a11"), None);
+    }
+
+    #[test]
+    fn test_corners_are_tried_first() {
+        for &size in &[6, 8] {
+            let mut b = Board::new_start_sized(size);
+            b.set_at(Pos2D::new(1, 1), Color::White);
+            b.set_at(Pos2D::new(2, 2), Color::Black);
+            b.set_at(Pos2D::new(1, 0), Color::White);
+            b.set_at(Pos2D::new(2, 0), Color::Black);
+            assert_eq!(b.get_available_moves_for(Color::Black).next(), Some(Pos2D::new(0, 0)));
+        }
+    }
+
+    #[test]
+    fn test_moves_into_empty_center() {
+        // a set-up position where d3 in the middle is still empty
+        let mut b = Board::new();
+        b.set_at_c('d', 2, Color::White);
+        b.set_at_c('d', 1, Color::Black);
+        assert!(b.can_place(Pos2D::new(3, 3), Color::Black));
+        assert_eq!(b.get_available_moves_for(Color::Black).collect::<Vec<_>>(), vec![Pos2D::new(3, 3)]);
+    }
+
+    fn get_board_with_holes() -> Board {
+        // first row: X O # O - and a column of White under e0 ending with Black
+        let mut b = Board::new();
+        b.set_at_c('a', 0, Color::Black);
+        b.set_at_c('b', 0, Color::White);
+        b.set_blocked(Pos2D::new(2, 0), true);
+        b.set_at_c('d', 0, Color::White);
+        b.set_at_c('e', 1, Color::White);
+        b.set_at_c('e', 2, Color::Black);
+        b
+    }
+
+    #[test]
+    fn test_flips_stop_at_holes() {
+        let mut b = get_board_with_holes();
+        assert!(!b.can_place(Pos2D::new(2, 0), Color::Black));
+        assert!(b.get_available_moves_for(Color::Black).all(|p| !b.is_blocked(p)));
+
+        // e0 only flips the column: the row is cut off by the hole at c0
+        b.place(Pos2D::new(4, 0), Color::Black);
+        assert_eq!(b.get_at_c('e', 1), Color::Black);
+        assert_eq!(b.get_at_c('d', 0), Color::White);
+        assert_eq!(b.get_at_c('b', 0), Color::White);
+        assert_eq!(b.get_at_c('c', 0), Color::Empty);
+
+        // without the hole, c0 would not be blocked and b0 is flanked from a0
+        let mut b = get_board_with_holes();
+        b.set_blocked(Pos2D::new(2, 0), false);
+        assert!(b.can_place(Pos2D::new(2, 0), Color::Black));
+    }
+
+    #[test]
+    fn test_holes_in_board_notation() {
+        let b = get_board_with_holes();
+        let cells = b.to_string();
+        assert_eq!(&cells[..5], "XO#O-");
+        let parsed: Board = cells.parse().unwrap();
+        assert!(parsed.is_blocked(Pos2D::new(2, 0)));
+        assert_eq!(parsed.to_string(), cells);
+        assert_eq!(parsed.num_empty(), 64 - 5 - 1);
+        assert_eq!(parsed.num_occupied(), 5);
+    }
 }
@@ -0,0 +1,86 @@
+#![allow(dead_code)]
+
+use rand::Rng;
+
+use crate::board::*;
+use crate::position::Position;
+use crate::review::read_transcripts;
+use crate::symmetry::PositionMap;
+use crate::transcript::*;
+
+pub const BOOK_LINES: &str = include_str!("book.txt");
+
+// Moves to play in the opening, with how many of the games the book was made from
+// played them. Symmetric positions share their moves.
+pub struct Book {
+    moves: PositionMap<Vec<(Pos2D, i32)>>,
+}
+
+impl Book {
+    pub fn from_transcripts(games: &[Transcript]) -> Book {
+        let mut moves = PositionMap::new();
+        for game in games {
+            let mut board = game.start_board();
+            for &(mover, p) in &game.moves {
+                if !board.can_place(p, mover) {
+                    break;
+                }
+                moves.add_move(&Position::new(&board, mover), p, 1);
+                board.place(p, mover);
+            }
+        }
+        Book { moves }
+    }
+
+    // The lines in book.txt, which the engine chose with searches 5 moves deep. They are
+    // its own view of the opening, not lines from master games.
+    pub fn builtin() -> Book {
+        let games: Vec<Transcript> = BOOK_LINES
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(Transcript::from_trace)
+            .collect();
+        Book::from_transcripts(&games)
+    }
+
+    // A book from a file of games, one transcript per line
+    pub fn load(path: &str) -> Result<Book, String> {
+        Ok(Book::from_transcripts(&read_transcripts(path)?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    // The book moves in the position, each with the number of games that played it,
+    // the most played first
+    pub fn moves(&self, position: &Position) -> Vec<(Pos2D, i32)> {
+        let mut counted: Vec<(Pos2D, i32)> = vec![];
+        for (mv, count) in self.moves.moves(position) {
+            match counted.iter_mut().find(|(p, _)| *p == mv) {
+                Some((_, total)) => *total += count,
+                None => counted.push((mv, count)),
+            }
+        }
+        counted.sort_by_key(|&(mv, count)| (-count, mv.j, mv.i));
+        counted
+    }
+
+    // A book move for the position, the more often played ones more likely
+    pub fn pick<R: Rng>(&self, position: &Position, rng: &mut R) -> Option<Pos2D> {
+        let moves = self.moves(position);
+        let total: i32 = moves.iter().map(|&(_, count)| count).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut n = rng.gen_range(0, total);
+        for (mv, count) in moves {
+            if n < count {
+                return Some(mv);
+            }
+            n -= count;
+        }
+        None
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod test {
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::board::*;
+    use crate::book::*;
+    use crate::color::*;
+    use crate::position::*;
+    use crate::transcript::*;
+
+    fn book() -> Book {
+        let games: Vec<Transcript> = ["bc4wc5", "bc4wc3", "bc4wc5bf3"].iter().map(|t| Transcript::from_trace(t)).collect();
+        Book::from_transcripts(&games)
+    }
+
+    fn after(moves: &str) -> Position {
+        let transcript = Transcript::from_trace(moves);
+        let mut board = Board::new_start();
+        for &(mover, p) in &transcript.moves {
+            board.place(p, mover);
+        }
+        Position::new(&board, Color::White)
+    }
+
+    #[test]
+    fn test_counts_games() {
+        let book = book();
+        assert_eq!(book.moves(&Position::new(&Board::new_start(), Color::Black)), vec![(Pos2D::new(2, 4), 3)]);
+        assert_eq!(book.moves(&after("bc4")), vec![(Pos2D::new(2, 5), 2), (Pos2D::new(2, 3), 1)]);
+        assert!(book.moves(&after("bc4wc5bf3")).is_empty());
+    }
+
+    #[test]
+    fn test_symmetric_positions_share_moves() {
+        // e2 is c4 mirrored in the diagonal through the start discs
+        assert_eq!(book().moves(&after("be2")), vec![(Pos2D::new(5, 2), 2), (Pos2D::new(3, 2), 1)]);
+    }
+
+    #[test]
+    fn test_picks_by_count() {
+        let book = book();
+        let mut rng = StdRng::seed_from_u64(1);
+        let picks: Vec<Pos2D> = (0..300).filter_map(|_| book.pick(&after("bc4"), &mut rng)).collect();
+        let c5 = picks.iter().filter(|&&p| p == Pos2D::new(2, 5)).count();
+        assert_eq!(picks.len(), 300);
+        assert!(c5 > 150 && c5 < 250);
+        assert_eq!(book.pick(&after("bc4wc5bf3"), &mut rng), None);
+    }
+
+    #[test]
+    fn test_builtin_book() {
+        for line in BOOK_LINES.lines().filter(|l| !l.starts_with('#')) {
+            let transcript = Transcript::from_trace(line);
+            let mut board = Board::new_start();
+            for &(mover, p) in &transcript.moves {
+                assert!(board.can_place(p, mover), "{}", line);
+                board.place(p, mover);
+            }
+        }
+        // there is an answer to every first move
+        let book = Book::builtin();
+        for first in ["bc4", "bd5", "be2", "bf3"].iter() {
+            assert!(!book.moves(&after(first)).is_empty(), "{}", first);
+        }
+    }
+}
@@ -0,0 +1,180 @@
+#![allow(dead_code)]
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::color::Color;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeControl {
+    Unlimited,
+    // the whole game in this time
+    SuddenDeath(Duration),
+    // the base time, and the increment added after every move
+    Increment(Duration, Duration),
+    // this long for every move, with nothing carried over
+    PerMove(Duration),
+}
+
+fn parse_seconds(s: &str, unit: f64) -> Result<Duration, String> {
+    match s.parse::<f64>() {
+        Ok(n) if n >= 0.0 && n.is_finite() => Ok(Duration::from_secs_f64(n * unit)),
+        _ => Err(format!("bad time '{}'", s)),
+    }
+}
+
+impl FromStr for TimeControl {
+    type Err = String;
+
+    // "none", "<minutes>" for sudden death, "<minutes>+<seconds>" with an increment,
+    // or "move:<seconds>" for a fixed time per move
+    fn from_str(s: &str) -> Result<TimeControl, String> {
+        let s = s.trim().to_lowercase();
+        if s == "none" {
+            return Ok(TimeControl::Unlimited);
+        }
+        if let Some(seconds) = s.strip_prefix("move:") {
+            return Ok(TimeControl::PerMove(parse_seconds(seconds, 1.0)?));
+        }
+        match s.split_once('+') {
+            Some((base, increment)) => Ok(TimeControl::Increment(parse_seconds(base, 60.0)?, parse_seconds(increment, 1.0)?)),
+            None => Ok(TimeControl::SuddenDeath(parse_seconds(&s, 60.0)?)),
+        }
+    }
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeControl::Unlimited => write!(f, "no time limit"),
+            TimeControl::SuddenDeath(base) => write!(f, "{} for the game", format_time(*base)),
+            TimeControl::Increment(base, increment) => {
+                write!(f, "{} plus {:.1}s per move", format_time(*base), increment.as_secs_f64())
+            }
+            TimeControl::PerMove(limit) => write!(f, "{:.1}s per move", limit.as_secs_f64()),
+        }
+    }
+}
+
+pub fn format_time(d: Duration) -> String {
+    let tenths = d.as_millis() / 100;
+    if tenths < 600 {
+        format!("{}.{}s", tenths / 10, tenths % 10)
+    } else {
+        format!("{}:{:02}", tenths / 600, tenths / 10 % 60)
+    }
+}
+
+fn side(color: Color) -> usize {
+    if color == Color::Black { 0 } else { 1 }
+}
+
+// A chess clock for both sides
+pub struct Clock {
+    pub control: TimeControl,
+    remaining: [Duration; 2],
+    flagged: [bool; 2],
+    // the side whose clock is running, and since when
+    running: Option<(Color, Instant)>,
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Clock {
+        let base = match control {
+            TimeControl::Unlimited => Duration::from_secs(0),
+            TimeControl::SuddenDeath(base) | TimeControl::Increment(base, _) | TimeControl::PerMove(base) => base,
+        };
+        Clock {
+            control,
+            remaining: [base; 2],
+            flagged: [false; 2],
+            running: None,
+        }
+    }
+
+    // Starts the clock of the side to move, stopping the other one
+    pub fn start(&mut self, color: Color) {
+        self.stop();
+        if let TimeControl::PerMove(limit) = self.control {
+            self.remaining[side(color)] = limit;
+        }
+        self.running = Some((color, Instant::now()));
+    }
+
+    // Stops the running clock
+    pub fn stop(&mut self) {
+        if let Some((color, since)) = self.running.take() {
+            self.charge(color, since.elapsed());
+        }
+    }
+
+    // Stops the clock of `color`, who has just moved
+    pub fn moved(&mut self, color: Color) {
+        self.stop();
+        if let TimeControl::Increment(_, increment) = self.control {
+            if !self.flagged[side(color)] {
+                self.remaining[side(color)] += increment;
+            }
+        }
+    }
+
+    // Takes time off the clock of `color`
+    pub fn charge(&mut self, color: Color, elapsed: Duration) {
+        if self.control == TimeControl::Unlimited {
+            return;
+        }
+        let remaining = &mut self.remaining[side(color)];
+        if elapsed > *remaining {
+            self.flagged[side(color)] = true;
+        }
+        *remaining = remaining.saturating_sub(elapsed);
+    }
+
+    // The time left to `color`, for the current move with a fixed time per move
+    pub fn remaining(&self, color: Color) -> Option<Duration> {
+        if self.control == TimeControl::Unlimited {
+            return None;
+        }
+        let running = match self.running {
+            Some((running, since)) if running == color => since.elapsed(),
+            _ => Duration::from_secs(0),
+        };
+        Some(self.remaining[side(color)].saturating_sub(running))
+    }
+
+    pub fn out_of_time(&self, color: Color) -> bool {
+        self.flagged[side(color)] || self.remaining(color) == Some(Duration::from_secs(0))
+    }
+
+    // How long `color` may think about its next move, with `empties` cells left to fill.
+    // The time left is spread over the moves still to come, keeping a little in reserve.
+    pub fn budget(&self, color: Color, empties: usize) -> Option<Duration> {
+        let remaining = self.remaining(color)?;
+        let reserve = (remaining / 10).clamp(Duration::from_millis(20), Duration::from_secs(1)).min(remaining);
+        let available = remaining - reserve;
+        Some(match self.control {
+            TimeControl::PerMove(_) => available,
+            TimeControl::Increment(_, increment) => {
+                (available / moves_to_go(empties) + increment * 3 / 4).min(available)
+            }
+            _ => available / moves_to_go(empties),
+        })
+    }
+}
+
+// The moves of one side still to come, each side filling about half of the empty cells
+fn moves_to_go(empties: usize) -> u32 {
+    empties.div_ceil(2).max(1) as u32
+}
+
+impl fmt::Display for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let show = |color| match self.remaining(color) {
+            Some(left) if self.out_of_time(color) => format!("{} (out of time)", format_time(left)),
+            Some(left) => format_time(left),
+            None => String::from("-"),
+        };
+        write!(f, "Black {}  White {}", show(Color::Black), show(Color::White))
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod test {
+
+    use std::time::Duration;
+
+    use crate::clock::*;
+    use crate::color::*;
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn test_parse_time_controls() {
+        assert_eq!("none".parse::<TimeControl>(), Ok(TimeControl::Unlimited));
+        assert_eq!("5".parse::<TimeControl>(), Ok(TimeControl::SuddenDeath(secs(300))));
+        assert_eq!("0.5".parse::<TimeControl>(), Ok(TimeControl::SuddenDeath(secs(30))));
+        assert_eq!("3+2".parse::<TimeControl>(), Ok(TimeControl::Increment(secs(180), secs(2))));
+        assert_eq!("move:10".parse::<TimeControl>(), Ok(TimeControl::PerMove(secs(10))));
+        assert!("fast".parse::<TimeControl>().is_err());
+        assert!("-1".parse::<TimeControl>().is_err());
+        assert_eq!(TimeControl::Increment(secs(180), secs(2)).to_string(), "3:00 plus 2.0s per move");
+        assert_eq!(format_time(Duration::from_millis(9_950)), "9.9s");
+    }
+
+    #[test]
+    fn test_increments_and_flags() {
+        let mut clock = Clock::new(TimeControl::Increment(secs(60), secs(2)));
+        clock.charge(Color::Black, secs(10));
+        clock.moved(Color::Black);
+        assert_eq!(clock.remaining(Color::Black), Some(secs(52)));
+        assert_eq!(clock.remaining(Color::White), Some(secs(60)));
+
+        clock.charge(Color::White, secs(61));
+        clock.moved(Color::White);
+        assert!(clock.out_of_time(Color::White));
+        assert!(!clock.out_of_time(Color::Black));
+        assert_eq!(clock.remaining(Color::White), Some(secs(0)));
+        assert_eq!(clock.to_string(), "Black 52.0s  White 0.0s (out of time)");
+
+        let mut unlimited = Clock::new(TimeControl::Unlimited);
+        unlimited.charge(Color::Black, secs(1000));
+        assert!(!unlimited.out_of_time(Color::Black));
+        assert_eq!(unlimited.budget(Color::Black, 60), None);
+    }
+
+    #[test]
+    fn test_budgets() {
+        // a sudden death clock is shared out over the moves left, less a second in reserve
+        let clock = Clock::new(TimeControl::SuddenDeath(secs(61)));
+        assert_eq!(clock.budget(Color::Black, 60), Some(secs(2)));
+        assert_eq!(clock.budget(Color::Black, 2), Some(secs(60)));
+
+        let clock = Clock::new(TimeControl::Increment(secs(30), secs(4)));
+        assert_eq!(clock.budget(Color::Black, 58), Some(secs(4)));
+
+        // with a fixed time per move, the move may use all of it but the reserve
+        let mut clock = Clock::new(TimeControl::PerMove(secs(10)));
+        assert_eq!(clock.budget(Color::White, 40), Some(secs(9)));
+        clock.charge(Color::White, secs(4));
+        clock.start(Color::White);
+        assert!(clock.remaining(Color::White).unwrap() > secs(9));
+    }
+}
@@ -1,8 +1,9 @@
 #![allow(dead_code)]
 
+use serde::Serialize;
 use std::fmt;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum Color {
     Empty,
     Black,
@@ -26,10 +27,7 @@ impl fmt::Display for Color {
 
 impl Color {
     pub fn is_empty(&self) -> bool {
-        match self {
-            Color::Empty => true,
-            _ => false,
-        }
+        matches!(self, Color::Empty)
     }
 
     pub fn opposite(&self) -> Color {
@@ -0,0 +1,162 @@
+#![allow(dead_code)]
+
+use std::fmt;
+
+use crate::analysis::*;
+use crate::board::*;
+use crate::color::Color;
+use crate::params::*;
+
+// The terms eval() is made of; the tutor explains moves by them
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Term {
+    Mobility,
+    Frontier,
+    Corners,
+    XSquares,
+    CSquares,
+    EdgeRuns,
+    Parity,
+    Discs,
+}
+
+impl Term {
+    pub fn name(self) -> &'static str {
+        match self {
+            Term::Mobility => "mobility",
+            Term::Frontier => "frontier",
+            Term::Corners => "corners",
+            Term::XSquares => "X-squares",
+            Term::CSquares => "C-squares",
+            Term::EdgeRuns => "edge runs",
+            Term::Parity => "parity",
+            Term::Discs => "discs",
+        }
+    }
+}
+
+// One term of the evaluation: the raw feature for each side and the weight applied
+// to their difference
+pub struct EvalTerm {
+    pub term: Term,
+    pub own: i32,
+    pub opponent: i32,
+    pub weight: i32,
+}
+
+impl EvalTerm {
+    pub fn score(&self) -> i32 {
+        (self.own - self.opponent) * self.weight
+    }
+}
+
+pub struct EvalBreakdown {
+    pub color: Color,
+    pub endgame: bool,
+    pub terms: Vec<EvalTerm>,
+}
+
+impl EvalBreakdown {
+    pub fn total(&self) -> i32 {
+        self.terms.iter().map(|t| t.score()).sum()
+    }
+}
+
+// Breaks eval() down into its individual terms, scored from the point of view of `color`,
+// which is assumed to be the side to move. The terms always add up to eval().
+pub fn explain_eval(board: &Board, color: Color, params: &EvalParams) -> EvalBreakdown {
+    let opposite = color.opposite();
+    let occupied = game_phase(board);
+    let endgame = occupied >= params.count_from;
+    let weights = params.weights_for(occupied);
+    let midgame_weight = |weight: i32| if endgame { 0 } else { weight };
+    // in anti-reversi the corner terms and the disc count work the other way round
+    let sign = board.variant().disc_sign();
+
+    let (mobility, oppo_mobility) = board.count_available_moves(color, opposite);
+    let own_corners = corner_features(board, color);
+    let oppo_corners = corner_features(board, opposite);
+    let own_parity = if parity(board) > 0 { 1 } else { 0 };
+
+    let terms = vec![
+        EvalTerm {
+            term: Term::Mobility,
+            own: mobility,
+            opponent: oppo_mobility,
+            weight: midgame_weight(weights.mobility),
+        },
+        EvalTerm {
+            term: Term::Frontier,
+            own: count_frontier(board, color),
+            opponent: count_frontier(board, opposite),
+            weight: midgame_weight(weights.frontier),
+        },
+        EvalTerm {
+            term: Term::Corners,
+            own: own_corners.corners,
+            opponent: oppo_corners.corners,
+            weight: midgame_weight(weights.corner) * sign,
+        },
+        EvalTerm {
+            term: Term::XSquares,
+            own: own_corners.x_squares,
+            opponent: oppo_corners.x_squares,
+            weight: midgame_weight(weights.x_square) * sign,
+        },
+        EvalTerm {
+            term: Term::CSquares,
+            own: own_corners.c_squares,
+            opponent: oppo_corners.c_squares,
+            weight: midgame_weight(weights.c_square) * sign,
+        },
+        EvalTerm {
+            term: Term::EdgeRuns,
+            own: own_corners.edge_runs,
+            opponent: oppo_corners.edge_runs,
+            weight: midgame_weight(weights.edge_run) * sign,
+        },
+        EvalTerm {
+            term: Term::Parity,
+            own: own_parity,
+            opponent: 1 - own_parity,
+            weight: midgame_weight(weights.parity),
+        },
+        EvalTerm {
+            term: Term::Discs,
+            own: board.num_of_color(color) as i32,
+            opponent: board.num_of_color(opposite) as i32,
+            weight: if endgame { sign } else { 0 },
+        },
+    ];
+
+    EvalBreakdown { color, endgame, terms }
+}
+
+impl fmt::Display for EvalBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Evaluation for {} ({}):",
+            self.color,
+            if self.endgame { "endgame, disc count" } else { "midgame" }
+        )?;
+        writeln!(f, "  {:<16}{:>6}{:>6}{:>8}{:>8}", "term", "own", "opp", "weight", "score")?;
+        for term in &self.terms {
+            write!(
+                f,
+                "  {:<16}{:>6}{:>6}{:>8}{:>8}",
+                term.term.name(),
+                term.own,
+                term.opponent,
+                term.weight,
+                term.score()
+            )?;
+            // shown for information only: a term without weight adds nothing to the total
+            if term.weight == 0 {
+                write!(f, "  (informational)")?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "  {:<16}{:>28}", "total", self.total())
+    }
+}
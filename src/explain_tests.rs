@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod test {
+
+    use crate::analysis::*;
+    use crate::board::*;
+    use crate::color::*;
+    use crate::explain::*;
+    use crate::params::*;
+
+    fn get_midgame_board() -> Board {
+        let mut b = Board::new();
+        b.set_at_c('A', 0, Color::Black);
+        b.set_at_c('B', 0, Color::Black);
+        b.set_at_c('C', 0, Color::Black);
+        b.set_at_c('A', 1, Color::Black);
+        b.set_at_c('G', 6, Color::White);
+        b.set_at_c('H', 6, Color::White);
+        b.set_at_c('D', 3, Color::Black);
+        b.set_at_c('D', 4, Color::White);
+        b.set_at_c('E', 3, Color::White);
+        b.set_at_c('E', 4, Color::Black);
+        b
+    }
+
+    #[test]
+    fn test_breakdown_adds_up_to_eval() {
+        let b = get_midgame_board();
+        for color in &[Color::Black, Color::White] {
+            for cutoff in &[1, 52] {
+                let params = EvalParams::counting_from(*cutoff);
+                let breakdown = explain_eval(&b, *color, &params);
+                assert_eq!(breakdown.total(), eval(&b, *color, &params));
+            }
+        }
+    }
+
+    #[test]
+    fn test_breakdown_terms() {
+        let b = get_midgame_board();
+        let breakdown = explain_eval(&b, Color::White, &EvalParams::default());
+        let term = |term| breakdown.terms.iter().find(|t| t.term == term).unwrap();
+
+        assert!(!breakdown.endgame);
+        assert_eq!(term(Term::Corners).opponent, 1);
+        assert_eq!(term(Term::EdgeRuns).opponent, 3);
+        assert_eq!(term(Term::XSquares).own, 1);
+        assert_eq!(term(Term::CSquares).own, 1);
+        assert_eq!(term(Term::Discs).weight, 0);
+    }
+
+    #[test]
+    fn test_unweighted_terms_are_informational() {
+        let b = get_midgame_board();
+        let text = explain_eval(&b, Color::White, &EvalParams::default()).to_string();
+        let row = |name| text.lines().find(|line| line.trim_start().starts_with(name)).unwrap().to_string();
+        assert!(row("frontier").ends_with("(informational)"));
+        assert!(row("parity").ends_with("(informational)"));
+        assert!(!row("corners").contains("informational"));
+        assert!(!row("total").contains("informational"));
+    }
+
+    #[test]
+    fn test_breakdown_with_all_weights_set() {
+        let b = get_midgame_board();
+        let mut params = EvalParams::default();
+        params.set("frontier=-3").unwrap();
+        params.set("c_square=-20").unwrap();
+        params.set("parity=7").unwrap();
+        for color in &[Color::Black, Color::White] {
+            let breakdown = explain_eval(&b, *color, &params);
+            assert_eq!(breakdown.total(), eval(&b, *color, &params));
+        }
+    }
+
+    #[test]
+    fn test_anti_reversi_inverts_corners_and_discs() {
+        let mut b = get_midgame_board();
+        b.set_variant(Variant::Anti);
+        let params = EvalParams::default();
+        for color in &[Color::Black, Color::White] {
+            for cutoff in &[1, 52] {
+                let params = EvalParams::counting_from(*cutoff);
+                assert_eq!(explain_eval(&b, *color, &params).total(), eval(&b, *color, &params));
+            }
+        }
+        let normal = explain_eval(&get_midgame_board(), Color::Black, &params);
+        let anti = explain_eval(&b, Color::Black, &params);
+        let weight = |breakdown: &EvalBreakdown, term| breakdown.terms.iter().find(|t| t.term == term).unwrap().weight;
+        assert_eq!(weight(&anti, Term::Corners), -weight(&normal, Term::Corners));
+        assert_eq!(weight(&anti, Term::Mobility), weight(&normal, Term::Mobility));
+        assert_eq!(eval(&b, Color::Black, &EvalParams::counting_from(1)), -2);
+    }
+}
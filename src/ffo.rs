@@ -0,0 +1,124 @@
+#![allow(dead_code)]
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use crate::board::*;
+use crate::color::Color;
+use crate::solve::*;
+use crate::stat::Stat;
+
+// Positions from the FFO endgame test suite, one per line:
+// number, the 64 cells, the side to move, the best moves separated by '/', the exact score.
+// Only #40 to #43 are built in; the rest of #40-#79 is out of scope and can be given in a file.
+pub const FFO_POSITIONS: &str = include_str!("ffo.txt");
+
+pub struct TestPosition {
+    pub number: u32,
+    pub board: Board,
+    pub to_move: Color,
+    pub best_moves: Vec<Pos2D>,
+    pub score: i32,
+}
+
+impl TestPosition {
+    pub fn parse(line: &str) -> Result<TestPosition, String> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("expected 5 fields in '{}'", line));
+        }
+        let number = fields[0].parse().map_err(|_| format!("bad number '{}'", fields[0]))?;
+        let board = fields[1].parse()?;
+        let to_move = match fields[2] {
+            "X" => Color::Black,
+            "O" => Color::White,
+            other => return Err(format!("bad side to move '{}'", other)),
+        };
+        let best_moves = fields[3]
+            .split('/')
+            .map(|m| Pos2D::from_standard(m).ok_or_else(|| format!("bad move '{}'", m)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let score = fields[4].parse().map_err(|_| format!("bad score '{}'", fields[4]))?;
+        Ok(TestPosition { number, board, to_move, best_moves, score })
+    }
+}
+
+pub fn parse_positions(text: &str) -> Result<Vec<TestPosition>, String> {
+    text.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(TestPosition::parse)
+        .collect()
+}
+
+pub struct FfoOptions {
+    pub first: u32,
+    pub last: u32,
+    // use positions from this file instead of the built-in ones
+    pub file: Option<String>,
+}
+
+impl Default for FfoOptions {
+    fn default() -> FfoOptions {
+        FfoOptions { first: 40, last: 43, file: None }
+    }
+}
+
+fn speed(nodes: u64, elapsed: Duration) -> u64 {
+    (nodes as f64 / 1000.0 / elapsed.as_secs_f64().max(1e-9)) as u64
+}
+
+pub fn run_ffo(options: &FfoOptions) -> Result<(), String> {
+    let text = match &options.file {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?,
+        None => FFO_POSITIONS.to_string(),
+    };
+    let positions: Vec<_> = parse_positions(&text)?
+        .into_iter()
+        .filter(|p| p.number >= options.first && p.number <= options.last)
+        .collect();
+
+    let mut passed = 0;
+    let mut total_nodes = 0;
+    let mut total_time = Duration::from_secs(0);
+    for position in &positions {
+        let mut stat = Stat::new();
+        let start = Instant::now();
+        let (score, best) = solve_exact(&position.board, position.to_move, &mut stat);
+        let elapsed = start.elapsed();
+
+        let move_ok = best.is_some_and(|m| position.best_moves.contains(&m));
+        let ok = move_ok && score == position.score;
+        if ok {
+            passed += 1;
+        }
+        total_nodes += stat.nodes_viewed;
+        total_time += elapsed;
+        println!(
+            "#{} {} empties: {} {:+} (expected {} {:+}) {}. Nodes {}. Elapsed {:?}. Speed: {}Knodes/sec",
+            position.number,
+            position.board.num_empty(),
+            best.map_or(String::from("pass"), |m| m.to_standard()),
+            score,
+            position.best_moves.iter().map(|m| m.to_standard()).collect::<Vec<_>>().join("/"),
+            position.score,
+            if ok { "ok" } else { "FAILED" },
+            stat.nodes_viewed,
+            elapsed,
+            speed(stat.nodes_viewed, elapsed)
+        );
+    }
+    println!(
+        "Solved {} of {} correctly. Nodes {}. Elapsed {:?}. Speed: {}Knodes/sec",
+        passed,
+        positions.len(),
+        total_nodes,
+        total_time,
+        speed(total_nodes, total_time)
+    );
+    if passed == positions.len() {
+        Ok(())
+    } else {
+        Err(format!("{} positions solved incorrectly", positions.len() - passed))
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod test {
+
+    use rand::rngs::StdRng;
+    use rand::seq::IteratorRandom;
+    use rand::SeedableRng;
+
+    use crate::board::*;
+    use crate::color::*;
+    use crate::ffo::*;
+    use crate::selfplay::*;
+    use crate::solve::*;
+    use crate::stat::Stat;
+
+    #[test]
+    fn test_standard_notation() {
+        assert_eq!(Pos2D::from_standard("A1"), Some(Pos2D::new(0, 0)));
+        assert_eq!(Pos2D::from_standard("h8"), Some(Pos2D::new(7, 7)));
+        assert_eq!(Pos2D::from_standard("c4"), Some(Pos2D::new(2, 3)));
+        assert_eq!(Pos2D::from_standard("k1"), None);
+        assert_eq!(Pos2D::from_standard("a0"), None);
+        assert_eq!(Pos2D::from_standard("a11"), None);
+        assert_eq!(Pos2D::new(2, 3).to_standard(), "c4");
+    }
+
+    #[test]
+    fn test_builtin_positions() {
+        let positions = parse_positions(FFO_POSITIONS).unwrap();
+        assert!(!positions.is_empty());
+        for p in &positions {
+            assert!((40..=79).contains(&p.number));
+            for mv in &p.best_moves {
+                assert!(p.board.can_place(*mv, p.to_move), "#{} {}", p.number, mv.to_standard());
+            }
+        }
+        // the default range is the positions that are built in
+        let options = FfoOptions::default();
+        assert_eq!(positions.first().unwrap().number, options.first);
+        assert_eq!(positions.last().unwrap().number, options.last);
+        assert_eq!(positions[0].number, 40);
+        assert_eq!(positions[0].to_move, Color::Black);
+        assert_eq!(64 - positions[0].board.num_occupied(), 20);
+    }
+
+    #[test]
+    #[ignore] // takes minutes even with optimizations
+    fn test_solve_ffo_40() {
+        let positions = parse_positions(FFO_POSITIONS).unwrap();
+        let p = &positions[0];
+        let (score, best) = solve_exact(&p.board, p.to_move, &mut Stat::new());
+        assert_eq!(score, 38);
+        assert_eq!(best, Some(Pos2D::from_standard("a2").unwrap()));
+    }
+
+    // Plain minimax over the whole remaining game, to check the solver against
+    fn exhaustive_score(board: &Board, color: Color) -> i32 {
+        let moves = board.get_available_moves_for(color).collect::<Vec<_>>();
+        if moves.is_empty() {
+            if !board.has_any_moves(color.opposite()) {
+                return final_score(board, color);
+            }
+            return -exhaustive_score(board, color.opposite());
+        }
+        moves
+            .iter()
+            .map(|mv| {
+                let mut child = Board::new_from(board);
+                child.place(*mv, color);
+                -exhaustive_score(&child, color.opposite())
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_solve_matches_exhaustive_search() {
+        let mut rng = StdRng::seed_from_u64(40);
+        for _ in 0..3 {
+            let opening = random_opening(56, &mut rng);
+            let mut b = Board::new_start();
+            let color = b.replay_transcript(&opening).opposite();
+            let (score, best) = solve_exact(&b, color, &mut Stat::new());
+            assert_eq!(score, exhaustive_score(&b, color), "{}", opening);
+            if let Some(mv) = best {
+                let mut child = Board::new_from(&b);
+                child.place(mv, color);
+                assert_eq!(-exhaustive_score(&child, color.opposite()), score);
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_4x4() {
+        // Perfect play on 4x4 ends 3-11 for White, with the two empty cells going to White
+        let b = Board::new_start_sized(4);
+        let (score, best) = solve_exact(&b, Color::Black, &mut Stat::new());
+        assert_eq!(score, -10);
+        assert!(b.can_place(best.unwrap(), Color::Black));
+    }
+
+    #[test]
+    fn test_solve_anti_reversi() {
+        let mut b = Board::new_start_sized(4);
+        b.set_variant(Variant::Anti);
+        let (score, _) = solve_exact(&b, Color::Black, &mut Stat::new());
+        assert_eq!(score, exhaustive_score(&b, Color::Black));
+
+        // Black has fewer discs here, so Black wins
+        let mut finished: Board = "XOOOOOOOOOOOOOOO".parse().unwrap();
+        assert_eq!(final_score(&finished, Color::Black), -14);
+        finished.set_variant(Variant::Anti);
+        assert_eq!(final_score(&finished, Color::Black), 14);
+    }
+
+    #[test]
+    fn test_solve_with_holes() {
+        let mut b = Board::new_start_sized(6);
+        for &(i, j) in &[(0, 0), (5, 1), (2, 4), (1, 1)] {
+            b.set_blocked(Pos2D::new(i, j), true);
+        }
+        let mut rng = StdRng::seed_from_u64(38);
+        // play down to a size the exhaustive search can handle
+        let mut color = Color::Black;
+        while b.num_empty() > 10 && (b.has_any_moves(color) || b.has_any_moves(color.opposite())) {
+            if let Some(mv) = b.get_available_moves_for(color).choose(&mut rng) {
+                b.place(mv, color);
+            }
+            color = color.opposite();
+        }
+        let (score, _) = solve_exact(&b, color, &mut Stat::new());
+        assert_eq!(score, exhaustive_score(&b, color));
+        assert!(score.abs() <= 36 - 4);
+    }
+}
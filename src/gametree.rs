@@ -0,0 +1,273 @@
+#![allow(dead_code)]
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::*;
+use crate::color::Color;
+use crate::position::Position;
+use crate::transcript::*;
+
+struct Node {
+    mv: Option<(Color, Pos2D)>,
+    parent: Option<usize>,
+    // the first child continues the main line, the others are variations
+    children: Vec<usize>,
+    // the child that redo goes to
+    selected: usize,
+}
+
+// A game with every line that was tried in it. It is written like a transcript, with
+// each variation in parentheses after the move it replaces: "bc4wc5(wc3bd2)bf3".
+pub struct GameTree {
+    pub variant: Variant,
+    // None for the standard starting position
+    pub start: Option<Position>,
+    nodes: Vec<Node>,
+    current: usize,
+}
+
+impl GameTree {
+    pub fn new(transcript: &Transcript) -> GameTree {
+        let mut tree = GameTree {
+            variant: transcript.variant,
+            start: transcript.start.clone(),
+            nodes: vec![Node { mv: None, parent: None, children: vec![], selected: 0 }],
+            current: 0,
+        };
+        for &(mover, p) in &transcript.moves {
+            tree.play(mover, p);
+        }
+        tree
+    }
+
+    // Goes on with the move, along the line that already has it if there is one
+    pub fn play(&mut self, mover: Color, p: Pos2D) {
+        let existing = self.nodes[self.current]
+            .children
+            .iter()
+            .position(|&child| self.nodes[child].mv == Some((mover, p)));
+        let index = match existing {
+            Some(index) => index,
+            None => {
+                self.nodes.push(Node { mv: Some((mover, p)), parent: Some(self.current), children: vec![], selected: 0 });
+                let child = self.nodes.len() - 1;
+                self.nodes[self.current].children.push(child);
+                self.nodes[self.current].children.len() - 1
+            }
+        };
+        self.nodes[self.current].selected = index;
+        self.current = self.nodes[self.current].children[index];
+    }
+
+    // Takes back up to `plies` moves, and returns how many it took back
+    pub fn undo(&mut self, plies: usize) -> usize {
+        let mut done = 0;
+        while done < plies {
+            match self.nodes[self.current].parent {
+                Some(parent) => self.current = parent,
+                None => break,
+            }
+            done += 1;
+        }
+        done
+    }
+
+    // Replays up to `plies` of the moves taken back, and returns how many it replayed
+    pub fn redo(&mut self, plies: usize) -> usize {
+        let mut done = 0;
+        while done < plies {
+            let node = &self.nodes[self.current];
+            match node.children.get(node.selected) {
+                Some(&child) => self.current = child,
+                None => break,
+            }
+            done += 1;
+        }
+        done
+    }
+
+    // Goes to the position after `ply` moves of the current line
+    pub fn goto(&mut self, ply: usize) -> Result<(), String> {
+        let current = self.current;
+        let now = self.ply();
+        if ply <= now {
+            self.undo(now - ply);
+        } else if self.redo(ply - now) != ply - now {
+            self.current = current;
+            return Err(format!("the line has only {} moves", self.line_length()));
+        }
+        Ok(())
+    }
+
+    // The number of moves played to get here
+    pub fn ply(&self) -> usize {
+        self.path().len()
+    }
+
+    // The number of moves in the current line, including those that were taken back
+    pub fn line_length(&self) -> usize {
+        let mut length = self.ply();
+        let mut node = &self.nodes[self.current];
+        while let Some(&child) = node.children.get(node.selected) {
+            length += 1;
+            node = &self.nodes[child];
+        }
+        length
+    }
+
+    // The moves from the start to here
+    fn path(&self) -> Vec<(Color, Pos2D)> {
+        let mut moves = vec![];
+        let mut node = self.current;
+        while let Some(parent) = self.nodes[node].parent {
+            moves.push(self.nodes[node].mv.unwrap());
+            node = parent;
+        }
+        moves.reverse();
+        moves
+    }
+
+    // The moves that have been tried from here, the one redo would replay first
+    pub fn next_moves(&self) -> Vec<(Color, Pos2D)> {
+        let node = &self.nodes[self.current];
+        let mut moves: Vec<_> = node.children.iter().map(|&child| self.nodes[child].mv.unwrap()).collect();
+        if !moves.is_empty() {
+            moves.swap(0, node.selected);
+        }
+        moves
+    }
+
+    fn header(&self) -> Transcript {
+        let mut transcript = Transcript::new();
+        transcript.variant = self.variant;
+        transcript.start = self.start.clone();
+        transcript
+    }
+
+    // The game up to here, as a transcript
+    pub fn transcript(&self) -> Transcript {
+        let mut transcript = self.header();
+        transcript.moves = self.path();
+        transcript
+    }
+
+    // The main line, without the variations
+    pub fn main_line(&self) -> Transcript {
+        let mut transcript = self.header();
+        let mut node = &self.nodes[0];
+        while let Some(&child) = node.children.first() {
+            node = &self.nodes[child];
+            transcript.moves.push(node.mv.unwrap());
+        }
+        transcript
+    }
+
+    pub fn board(&self) -> Board {
+        let transcript = self.transcript();
+        let mut board = transcript.start_board();
+        board.replay_transcript(&transcript);
+        board
+    }
+
+    // The side to move here, if it can move at all
+    pub fn to_move(&self) -> Color {
+        match self.nodes[self.current].mv {
+            Some((mover, _)) => mover.opposite(),
+            None => self.header().first_mover(),
+        }
+    }
+
+    fn write_line(&self, node: usize, output: &mut String) {
+        let mut node = node;
+        while let Some(&main) = self.nodes[node].children.first() {
+            let (mover, p) = self.nodes[main].mv.unwrap();
+            output.push_str(&format_move(mover, p));
+            for &variation in &self.nodes[node].children[1..] {
+                let (mover, p) = self.nodes[variation].mv.unwrap();
+                output.push('(');
+                output.push_str(&format_move(mover, p));
+                self.write_line(variation, output);
+                output.push(')');
+            }
+            node = main;
+        }
+    }
+}
+
+impl fmt::Display for GameTree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut output = self.header().to_string();
+        self.write_line(0, &mut output);
+        write!(f, "{}", output)
+    }
+}
+
+impl GameTree {
+    // Like parsing the text, but with the moves played from `start` if there is one, in
+    // place of any start the text gives
+    pub fn parse_from(s: &str, start: Option<&Position>) -> Result<GameTree, String> {
+        let (headers, moves): (Vec<&str>, Vec<&str>) = s.split_whitespace().partition(|word| word.contains('='));
+        let mut header = Transcript::parse(&headers.join(" "))?;
+        if let Some(start) = start {
+            header.start = Some(start.clone());
+        }
+        let mut tree = GameTree::new(&header);
+        let moves: Vec<u8> = moves.concat().into_bytes();
+
+        // where each open variation branched off
+        let mut branches = vec![];
+        let mut count = 0;
+        let mut rest = &moves[..];
+        while let Some(&c) = rest.first() {
+            match c {
+                b'(' => {
+                    branches.push(tree.current);
+                    if tree.undo(1) == 0 {
+                        return Err(String::from("a variation before the first move"));
+                    }
+                    rest = &rest[1..];
+                }
+                b')' => {
+                    tree.current = branches.pop().ok_or("unbalanced ')'")?;
+                    rest = &rest[1..];
+                }
+                _ if rest.len() >= 3 => {
+                    count += 1;
+                    let (mover, p) = parse_move(&rest[..3]).map_err(|e| format!("move {} {}", count, e))?;
+                    // Either side may only move in turn, unless the other one has to pass
+                    let board = tree.board();
+                    let turn = tree.to_move();
+                    if mover != turn && board.has_any_moves(turn) {
+                        return Err(format!("move {} is played by {:?}, but it is {:?}'s turn", count, mover, turn));
+                    }
+                    if !board.can_place(p, mover) {
+                        return Err(format!("move {} ({}) is not legal", count, format_move(mover, p)));
+                    }
+                    tree.play(mover, p);
+                    rest = &rest[3..];
+                }
+                _ => return Err(format!("move {} is cut short", count + 1)),
+            }
+        }
+        if !branches.is_empty() {
+            return Err(String::from("unbalanced '('"));
+        }
+        // Every redo follows the main line
+        tree.current = 0;
+        for node in 0..tree.nodes.len() {
+            tree.nodes[node].selected = 0;
+        }
+        tree.redo(usize::MAX);
+        Ok(tree)
+    }
+}
+
+impl FromStr for GameTree {
+    type Err = String;
+
+    // A transcript, possibly with variations. The position afterwards is the end of the main line.
+    fn from_str(s: &str) -> Result<GameTree, String> {
+        GameTree::parse_from(s, None)
+    }
+}
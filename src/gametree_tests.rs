@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod test {
+
+    use crate::board::*;
+    use crate::color::*;
+    use crate::gametree::*;
+    use crate::transcript::*;
+
+    #[test]
+    fn test_undo_redo_goto() {
+        let mut tree = GameTree::new(&Transcript::from_trace("bc4wc5bf3wb4"));
+        assert_eq!(tree.ply(), 4);
+        assert_eq!(tree.undo(3), 3);
+        assert_eq!(tree.to_move(), Color::White);
+        assert_eq!(tree.transcript().to_string(), "bc4");
+        // there is nothing before the start
+        assert_eq!(tree.undo(5), 1);
+        assert_eq!(tree.undo(1), 0);
+        assert_eq!(tree.redo(2), 2);
+        assert_eq!(tree.transcript().to_string(), "bc4wc5");
+        assert_eq!(tree.line_length(), 4);
+        tree.goto(4).unwrap();
+        assert_eq!(tree.redo(1), 0);
+        assert!(tree.goto(5).is_err());
+        assert_eq!(tree.ply(), 4);
+        tree.goto(1).unwrap();
+        assert_eq!(tree.board().num_occupied(), 5);
+    }
+
+    #[test]
+    fn test_variations() {
+        let mut tree = GameTree::new(&Transcript::from_trace("bc4wc5bf3"));
+        tree.undo(2);
+        tree.play(Color::White, Pos2D::new(2, 3));
+        tree.play(Color::Black, Pos2D::new(3, 2));
+        assert_eq!(tree.to_string(), "bc4wc5(wc3bd2)bf3");
+        assert_eq!(tree.main_line().to_string(), "bc4wc5bf3");
+
+        // redo follows the variation it came back along, playing the main move goes back to it
+        tree.undo(2);
+        assert_eq!(tree.next_moves(), vec![(Color::White, Pos2D::new(2, 3)), (Color::White, Pos2D::new(2, 5))]);
+        tree.play(Color::White, Pos2D::new(2, 5));
+        assert_eq!(tree.line_length(), 3);
+        assert_eq!(tree.to_string(), "bc4wc5(wc3bd2)bf3");
+    }
+
+    #[test]
+    fn test_parse_and_write() {
+        let text = "variant=anti bc4wc5(wc3bd2(bc2)we1)bf3(bd5)wb4";
+        let tree: GameTree = text.parse().unwrap();
+        assert_eq!(tree.to_string(), text);
+        assert_eq!(tree.variant, Variant::Anti);
+        // parsing ends at the end of the main line
+        assert_eq!(tree.transcript().to_string(), "variant=anti bc4wc5bf3wb4");
+        let mut tree = tree;
+        tree.goto(1).unwrap();
+        assert_eq!(tree.redo(3), 3);
+        assert_eq!(tree.transcript().to_string(), "variant=anti bc4wc5bf3wb4");
+
+        assert!("(bc4)".parse::<GameTree>().is_err());
+        assert!("bc4(wc3".parse::<GameTree>().is_err());
+        assert!("bc4)".parse::<GameTree>().is_err());
+        assert!("bc4wc".parse::<GameTree>().is_err());
+        assert!("bc4wz9".parse::<GameTree>().is_err());
+        // moves must be legal, and in turn unless the other side has to pass
+        assert!("bc4bc4".parse::<GameTree>().is_err());
+        assert!("bc4wc5(wa0)".parse::<GameTree>().is_err());
+        assert!("bc4bc5".parse::<GameTree>().is_err());
+    }
+}
@@ -21,9 +21,112 @@ use crate::stat::*;
 mod transcript;
 use crate::transcript::*;
 
+mod explain;
+use crate::explain::*;
+
+mod params;
+use crate::params::*;
+
+mod options;
+use crate::options::*;
+
+mod solve;
+use crate::solve::*;
+
+mod selfplay;
+use crate::selfplay::*;
+
+mod tune;
+use crate::tune::*;
+
+mod review;
+use crate::review::*;
+
+mod perft;
+use crate::perft::*;
+
+mod ffo;
+use crate::ffo::*;
+
+mod setup;
+use crate::setup::*;
+
+mod position;
+use crate::position::*;
+
+mod symmetry;
+use crate::symmetry::*;
+
+mod openings;
+use crate::openings::*;
+
+mod screen;
+use crate::screen::*;
+
+mod render;
+use crate::render::*;
+
+mod svg;
+use crate::svg::*;
+
+mod player;
+use crate::player::*;
+
+mod gametree;
+use crate::gametree::*;
+
+mod clock;
+use crate::clock::*;
+
+mod ponder;
+use crate::ponder::*;
+
+mod table;
+use crate::table::*;
+
+mod book;
+use crate::book::*;
+
+mod tutor;
+use crate::tutor::*;
+
+mod puzzle;
+use crate::puzzle::*;
+
+mod server;
+use crate::server::*;
+
+mod netgame;
+use crate::netgame::*;
+
 mod board_tests;
 mod analysis_tests;
+mod explain_tests;
+mod params_tests;
+mod tune_tests;
+mod review_tests;
+mod perft_tests;
+mod ffo_tests;
+mod setup_tests;
+mod position_tests;
+mod symmetry_tests;
+mod openings_tests;
+mod screen_tests;
+mod render_tests;
+mod svg_tests;
+mod player_tests;
+mod gametree_tests;
+mod clock_tests;
+mod ponder_tests;
+mod table_tests;
+mod book_tests;
+mod tutor_tests;
+mod puzzle_tests;
+mod server_tests;
+mod netgame_tests;
 
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::io::stdout;
 use std::io::Write;
 use std::io::{self, Read};
@@ -37,26 +140,199 @@ fn main() {
         .filter_module("reversi::stat", log::LevelFilter::Error)
         .init();
 
-    let mut board = Board::new();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    let options = match Options::from_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("{}", USAGE);
+            std::process::exit(1);
+        }
+    };
+    let params = options.eval_params;
+    let result = match options.command {
+        Command::Play(play_options) if play_options.tui => run_tui(&play_options, &params),
+        Command::Play(play_options) => play(&play_options, &params),
+        Command::Tune(tune_options) => run_tune(&tune_options, &params),
+        Command::Analyze(analyze_options) => run_analyze(&analyze_options, &params),
+        Command::Perft(perft_options) => {
+            run_perft(&perft_options);
+            Ok(())
+        }
+        Command::Ffo(ffo_options) => run_ffo(&ffo_options),
+        Command::Diagram(diagram_options) => run_diagram(&diagram_options, &params),
+        Command::Puzzles(puzzle_options) => run_puzzles(&puzzle_options, &params),
+        Command::Serve(serve_options) => run_serve(&serve_options, &params),
+        Command::Host(host_options) => run_host(&host_options),
+        Command::Connect(connect_options) => run_connect(&connect_options),
+        Command::Solve(solve_options) => {
+            run_solve(&solve_options);
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+// Puzzle mode: shows the puzzles one by one and checks the answers, until they run out or
+// the player has had enough
+fn train(path: &str, renderer: &Renderer) -> Result<(), String> {
+    let mut trainer = Trainer::new(load_puzzles(path)?);
+    println!("{} puzzles. Enter a move, skip, or q to go back to the game.", trainer.remaining());
+    while let Some(puzzle) = trainer.current() {
+        let (board, to_move) = (&puzzle.position.board, puzzle.position.to_move);
+        renderer.print(board, &Highlights { to_move: Some(to_move), ..Highlights::default() });
+        println!("Difficulty {}. {}", puzzle.difficulty, puzzle.prompt());
+        print!("puzzle> ");
+        stdout().flush().unwrap();
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).unwrap() == 0 {
+            break;
+        }
+        let input = input.trim().to_lowercase();
+        let (solution, score, next_best) = (puzzle.solution, puzzle.score, puzzle.next_best);
+        let answer = match input.as_str() {
+            "q" => break,
+            "skip" => None,
+            other => match Pos2D::parse(other).filter(|&mv| board.can_place(mv, to_move)) {
+                Some(mv) => Some(mv),
+                None => {
+                    println!("Error: not a legal move here. Try again.");
+                    continue;
+                }
+            },
+        };
+        let right = match answer {
+            Some(mv) => trainer.answer(mv),
+            None => {
+                trainer.skip();
+                false
+            }
+        };
+        let outcome = format!("ends {:+} with perfect play, the best other move {:+}", score, next_best);
+        if right {
+            println!("Right! {} {}. {}.", solution, outcome, trainer);
+        } else {
+            println!("The answer was {}, which {}. {}.", solution, outcome, trainer);
+        }
+    }
+    println!("Puzzles done. {}.", trainer);
+    Ok(())
+}
 
-    board.set_at_c('D', 3, Color::Black);
-    board.set_at_c('D', 4, Color::White);
-    board.set_at_c('E', 3, Color::White);
-    board.set_at_c('E', 4, Color::Black);
+fn swap_players(players: &mut Players) {
+    players.swap();
+    println!("Sides swapped. Black: {}, White: {}", players.black, players.white);
+}
 
-    let board_orig = Board::new_from(&board);
+// "level [black|white] <name>" makes the engine play that side at a difficulty level.
+// Without a side it is the side playing against `human`, who is to move.
+fn set_level(players: &mut Players, human: Color, input: &str) -> Result<(), String> {
+    let words: Vec<&str> = input.split_whitespace().skip(1).collect();
+    let (side, name) = match words.as_slice() {
+        ["black", name] => (Color::Black, *name),
+        ["white", name] => (Color::White, *name),
+        [name] => (human.opposite(), *name),
+        _ => return Err(format!("level [black|white] <name>, with a level out of {}", LEVELS.join(", "))),
+    };
+    let settings = EngineSettings::level(name)
+        .ok_or_else(|| format!("unknown level '{}', try one of {}", name, LEVELS.join(", ")))?;
+    *players.of_mut(side) = Player::Engine(settings);
+    println!("Black: {}, White: {}", players.black, players.white);
+    Ok(())
+}
 
-    let mut transcript = Transcript::new();
+// Moves around the game: "undo [n]" or "back [n]", "redo [n]" and "goto <ply>". Undo
+// goes on until a human is to move, since the engine would play the same move again.
+fn navigate(tree: &mut GameTree, players: &Players, input: &str) -> Result<(), String> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let count = match words.get(1) {
+        Some(n) => n.parse().map_err(|_| format!("'{}' is not a number of moves", n))?,
+        None if words[0] == "goto" => return Err(String::from("go to which move?")),
+        None => 1,
+    };
+    match words[0] {
+        "redo" => {
+            if tree.redo(count) == 0 {
+                return Err(String::from("nothing to redo"));
+            }
+        }
+        "goto" => tree.goto(count)?,
+        _ => {
+            if tree.undo(count) == 0 {
+                return Err(String::from("nothing to undo"));
+            }
+            while tree.ply() > 0 && !players.engines_only() && players.of(tree.to_move()) != &Player::Human {
+                tree.undo(1);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn load_game(path: &str) -> Result<GameTree, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+    text.lines().map(|l| l.trim()).find(|l| !l.is_empty()).unwrap_or("").parse()
+}
+
+fn play(options: &PlayOptions, params: &EvalParams) -> Result<(), String> {
+    // The hint is only a shallow search, so it switches to counting discs later
+    let hint_params = EvalParams {
+        count_from: 58,
+        ..params.clone()
+    };
+
+    // everything random in the game comes from the seed, so the same seed replays it
+    let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut rng = StdRng::seed_from_u64(seed);
+    let book = match &options.book {
+        Some(path) => Book::load(path)?,
+        None => Book::builtin(),
+    };
+
+    let mut start = match &options.position {
+        Some(position) => position.clone(),
+        None => options.start.position(options.size, &mut rng)?,
+    };
+    if options.setup {
+        if let Some((board, to_move)) = run_setup(&start.board, start.to_move, params) {
+            start = Position::new(&board, to_move);
+        }
+    }
+    let mut transcript = Transcript::starting_at(&start);
+    transcript.variant = options.variant;
     //let mut transcript = Transcript::  from_trace("bc4wc3bc2wb3ba4wd5bf3wb2bc5wa3ba2wd2be2wd1bc0wc1bd0wf4bf5we1be5we0bf0wf1bf2wg2bh2wf6bf7wg3bh3wg4bh4wc6bc7wh5be6wh1bg5wd6bd7wh6bg1");
     //let mut transcript = Transcript::from_trace("bc4wc5bc6wc3bc2wb3ba2wa3ba4we5bf2wd5be2wb5ba6");
     //let mut transcript = Transcript::from_trace("bc4wc5bf3wb4ba4wc3bb2wc2bb3wa2ba3wa5bd5wb5bc1wc0bb6wc6bb7wd7bd6we5be6wd2bd1we1bd0we0bf0wg0bb1wa7ba6wc7ba1wf7be7wa0bg7wf4bf5wg2bf2wh7bh2wb0wf1wg6bg4wh3bf6we2bg1wh0bh4wg3bh6wg5wh5");
 
-    let mut color = board.replay_transcript(&transcript).opposite();
+    let mut tree = GameTree::new(&transcript);
+    let mut board = tree.board();
+    let mut color = tree.to_move();
 
-    board.print();
+    let renderer = &options.renderer;
+    let mut players = options.players.clone();
+    let mut tutoring = options.tutor;
+    println!("Black: {}, White: {}", players.black, players.white);
+    println!("Seed: {}", seed);
+    let mut clock = Clock::new(options.time);
+    let ponder_results = PonderResults::default();
+    if options.time != TimeControl::Unlimited {
+        println!("Time control: {}", options.time);
+    }
+    renderer.print(&board, &Highlights { to_move: Some(color), ..Highlights::default() });
 
     loop {
-        println!("transcript: {}", transcript);
+        println!("transcript: {}", tree.transcript());
+        let ahead = tree.line_length() - tree.ply();
+        if ahead > 0 {
+            println!("({} {} taken back, redo replays them)", ahead, if ahead == 1 { "move" } else { "moves" });
+        }
         let score = caclulate_score(&board);
         println!(
             "Score: Black:{}  White:{}  Total:{}",
@@ -64,6 +340,9 @@ fn main() {
             score.1,
             board.num_occupied()
         );
+        if options.time != TimeControl::Unlimited {
+            println!("Clock: {}", clock);
+        }
         if board.has_any_moves(color) {
             println!("{:?} moves next", color);
         } else {
@@ -73,31 +352,65 @@ fn main() {
                 println!("Instead, enter next move for {:?}:", color);
             } else {
                 println!("{:?} also has no more moves. Game over.", color);
-                return;
+                clock.stop();
+                let score = final_score(&board, Color::Black);
+                let rule = if board.variant() == Variant::Anti { " with fewer discs" } else { "" };
+                if score > 0 {
+                    println!("Black wins{} by {}.", rule, score);
+                } else if score < 0 {
+                    println!("White wins{} by {}.", rule, -score);
+                } else {
+                    println!("It's a draw.");
+                }
+                return Ok(());
             }
         }
+        clock.start(color);
+        // stops thinking on the human's time once the turn is over
+        let mut ponderer: Option<Ponderer> = None;
+        // the tutor looks at the human's moves once per turn
+        let mut tutor: Option<Tutor> = None;
+        // false when the turn ends without a move, after undo, load or setup
+        let mut moved = false;
         loop {
-            if color == Color::White {
+            if let Player::Engine(settings) = players.of(color) {
+                let engine_params = settings.params.as_ref().unwrap_or(params);
                 let mut stat = Stat::new();
-                let (pos, score) = negamax(&board, color, 6, 52, &mut stat).unwrap();
-
-                // let (pos, score) = if color == Color::White 
-                //     {
-                //         negamax(&board, color, 4, 4, &mut stat).unwrap()
-                //     } else {
-                //         minimax(&board, color, 3, 3, &mut stat).unwrap()
-                //     };
+                // the weaker levels pick at random, so they don't use what was pondered
+                if options.ponder != Ponder::Off && settings.plays_best() {
+                    stat.table = Some(ponder_results.table.clone());
+                }
+                let pondered = match settings.plays_best() {
+                    true => pondered_move(&ponder_results, &Position::new(&board, color), settings.depth),
+                    false => None,
+                };
+                let (pos, score) = match pondered {
+                    Some(pondered) => {
+                        println!("Already thought about this at depth {}", pondered.depth);
+                        (pondered.mv, Some(pondered.score))
+                    }
+                    None => {
+                        let budget = clock.budget(color, board.num_empty());
+                        engine_move(&Position::new(&board, color), settings, budget, engine_params, &book, &mut rng, &mut stat).unwrap()
+                    }
+                };
+                clock.moved(color);
+                moved = true;
 
+                let before = Board::new_from(&board);
                 board.place(pos, color);
-                transcript.add(pos, color);
-                board.print();
+                tree.play(color, pos);
+                renderer.print(&board, &Highlights::after_move(&before, &board, pos, color));
                 let elapsed = stat.start.elapsed();
                 println!(
-                    "Computer ({}) picked {}. Reviewed {} nodes. Best score {}. Elapsed {:?}. Speed: {}.",
+                    "Computer ({}) picked {}. Reviewed {} nodes. {}. Elapsed {:?}. Speed: {}.",
                     color,
                     pos,
                     stat.nodes_viewed,
-                    score,
+                    match score {
+                        Some(score) => format!("Best score {}", score),
+                        None => String::from("From the book"),
+                    },
                     elapsed,
                     if elapsed.as_secs() == 0 {
                         format!("{}nodes/ms", ((stat.nodes_viewed as f64 / stat.start.elapsed().as_millis() as f64) as i32))
@@ -106,33 +419,155 @@ fn main() {
                         format!("{}Knodes/sec", (((stat.nodes_viewed / 1000) as f64 / stat.start.elapsed().as_secs() as f64) as i32))
                     }
                 );
+                if players.engines_only() {
+                    match options.pause {
+                        Pause::None => {}
+                        Pause::Millis(ms) => std::thread::sleep(std::time::Duration::from_millis(ms)),
+                        Pause::Step => {
+                            print!("[Enter] next move, swap, q> ");
+                            stdout().flush().unwrap();
+                            let mut input = String::new();
+                            io::stdin().read_line(&mut input).unwrap();
+                            match input.trim() {
+                                "q" => return Ok(()),
+                                "swap" => swap_players(&mut players),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
                 break;
             }
 
+            if ponderer.is_none() && options.ponder != Ponder::Off {
+                match players.of(color.opposite()) {
+                    Player::Engine(settings) if settings.plays_best() => {
+                        let engine_params = settings.params.as_ref().unwrap_or(params);
+                        ponderer = Some(Ponderer::start(&board, color, options.ponder, settings.depth, engine_params, &ponder_results));
+                    }
+                    _ => {}
+                }
+            }
+
             let hints = board.get_available_moves_for(color);
             print!("Options: ");
             for pat in hints {
                 print!("{} ", pat);
             }
-            let (pos, score) = minimax(&board, color, 3, 58, &mut Stat::new()).unwrap();
-            print!(". Hint: {} (score: {})", pos, score);
-            println!();
+            if tutoring {
+                let tutor = tutor.get_or_insert_with(|| Tutor::new(&board, color, 3, &hint_params));
+                println!(". {}", tutor.hint().unwrap());
+            } else {
+                let (pos, score) = minimax(&board, color, 3, &hint_params, &mut Stat::new()).unwrap();
+                print!(". Hint: {} (score: {})", pos, score);
+                println!();
+            }
 
             print!("> ");
             stdout().flush().unwrap();
             let mut input = String::new();
             let stdin = io::stdin();
             io::stdin().read_line(&mut input).unwrap();
-            let input = input.trim().to_lowercase();
+            let raw = input.trim().to_string();
+            let input = raw.to_lowercase();
             if input == "q" {
-                return;
+                return Ok(());
             }
-            else if input == "back" {
-                board = Board::new_from(&board_orig);
-                transcript.back();
-                color = board.replay_transcript(&transcript);
-                board.print();
-                break;
+            else if input == "swap" {
+                // the loop above lets the engine move at once if it now plays this side
+                ponderer = None;
+                ponder_results.clear();
+                swap_players(&mut players);
+                continue;
+            }
+            else if input == "level" || input.starts_with("level ") {
+                match set_level(&mut players, color, &input) {
+                    Ok(()) => {
+                        // the engine may now be to move, or ponder differently
+                        ponderer = None;
+                        ponder_results.clear();
+                    }
+                    Err(e) => println!("Error: {}", e),
+                }
+                continue;
+            }
+            else if input == "tutor" || input == "tutor on" || input == "tutor off" {
+                tutoring = input == "tutor on" || (input == "tutor" && !tutoring);
+                println!("Tutor {}", if tutoring { "on: bad moves are questioned before they are played" } else { "off" });
+                continue;
+            }
+            else if input == "puzzle" || input.starts_with("puzzle ") {
+                let path = raw[6..].trim();
+                match train(if path.is_empty() { "puzzles.txt" } else { path }, renderer) {
+                    // back to the game, as it was
+                    Ok(()) => renderer.print(&board, &Highlights { to_move: Some(color), ..Highlights::default() }),
+                    Err(e) => println!("Error: {}", e),
+                }
+                continue;
+            }
+            else if input == "eval" {
+                println!("{}", explain_eval(&board, color, params));
+                println!("{}", explain_eval(&board, color.opposite(), params));
+                continue;
+            }
+            else if input == "setup" {
+                if let Some((new_board, to_move)) = run_setup(&board, color, params) {
+                    let mut transcript = Transcript::starting_at(&Position::new(&new_board, to_move));
+                    transcript.variant = options.variant;
+                    tree = GameTree::new(&transcript);
+                    board = tree.board();
+                    // the table does not tell boards of other sizes or blocked cells apart
+                    ponder_results.clear();
+                    // the outer loop hands the move over once we break out
+                    color = to_move.opposite();
+                    renderer.print(&board, &Highlights { to_move: Some(to_move), ..Highlights::default() });
+                    break;
+                }
+                continue;
+            }
+            else if input.starts_with("save ") {
+                // the game records the rules and the variations too, so analyze can replay it
+                let path = raw[5..].trim();
+                match std::fs::write(path, format!("{}\n", tree)) {
+                    Ok(()) => println!("Saved the game to {}", path),
+                    Err(e) => println!("Error: cannot write {}: {}", path, e),
+                }
+                continue;
+            }
+            else if input.starts_with("load ") {
+                match load_game(raw[5..].trim()) {
+                    Ok(loaded) => {
+                        tree = loaded;
+                        board = tree.board();
+                        ponder_results.clear();
+                        color = tree.to_move().opposite();
+                        renderer.print(&board, &Highlights { to_move: Some(tree.to_move()), ..Highlights::default() });
+                        break;
+                    }
+                    Err(e) => println!("Error: {}", e),
+                }
+                continue;
+            }
+            else if input == "lines" {
+                let moves: Vec<String> = tree.next_moves().iter().map(|&(mover, p)| format_move(mover, p)).collect();
+                if moves.is_empty() {
+                    println!("No moves have been tried from here");
+                } else {
+                    println!("Tried from here, redo first: {}", moves.join(" "));
+                }
+                continue;
+            }
+            else if ["undo", "back", "redo", "goto"].contains(&input.split_whitespace().next().unwrap_or("")) {
+                match navigate(&mut tree, &players, &input) {
+                    Ok(()) => {
+                        board = tree.board();
+                        color = tree.to_move().opposite();
+                        renderer.print(&board, &Highlights { to_move: Some(tree.to_move()), ..Highlights::default() });
+                        break;
+                    }
+                    Err(e) => println!("Error: {}", e),
+                }
+                continue;
             }
             let coords = input.as_bytes();
             if coords.len() == 2 {
@@ -140,9 +575,23 @@ fn main() {
                 let yi = (coords[1] - 48) as usize;
                 let position = Pos2D::new(xi, yi);
                 if board.can_place(position, color) {
+                    let advice = tutor.as_ref().filter(|_| tutoring).and_then(|t| t.check(position, &Thresholds::default()));
+                    if let Some(advice) = advice {
+                        println!("{}", advice);
+                        print!("Play {} anyway? [y/N] ", position);
+                        stdout().flush().unwrap();
+                        let mut answer = String::new();
+                        io::stdin().read_line(&mut answer).unwrap();
+                        if !answer.trim().eq_ignore_ascii_case("y") {
+                            continue;
+                        }
+                    }
+                    let before = Board::new_from(&board);
                     board.place(position, color);
-                    transcript.add(position, color);
-                    board.print();
+                    clock.moved(color);
+                    moved = true;
+                    tree.play(color, position);
+                    renderer.print(&board, &Highlights::after_move(&before, &board, position, color));
                     break;
                 }
 
@@ -155,6 +604,11 @@ fn main() {
             }
         }
 
+        // only the side that just moved can have run out of time
+        if moved && options.lose_on_time && clock.out_of_time(color) {
+            println!("{:?} ran out of time. {:?} wins.", color, color.opposite());
+            return Ok(());
+        }
         color = color.opposite();
     }
 }
@@ -0,0 +1,360 @@
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::board::*;
+use crate::color::Color;
+use crate::position::Position;
+use crate::render::*;
+use crate::solve::final_score;
+use crate::transcript::*;
+
+// The line protocol, one command or message per line.
+//
+// From a client:
+//   NEW              start a game and wait for an opponent, playing Black
+//   JOIN <id>        take White in a waiting game
+//   WATCH <id>       follow a game
+//   LIST             the games and whether they are waiting or being played
+//   MOVE <c4>        play a move
+//   RESIGN           give up the game
+//   QUIT             leave; leaving a game that is being played loses it
+//
+// From the server, to everybody in the game where it is about the game:
+//   WELCOME, GAME <id> <Black|White|watching>, GAMES <id>:<waiting|playing> ...
+//   START <id>, BOARD <position>, MOVED <color> <move>, PASS <color>, TURN <color>
+//   OVER <Black|White|draw> <black discs>-<white discs> <end|resign|disconnect|abandoned>
+//   ERROR <message>
+pub const PROTOCOL: &str = "NEW, JOIN <id>, WATCH <id>, LIST, MOVE <move>, RESIGN or QUIT";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Role {
+    Player(Color),
+    Spectator,
+}
+
+struct NetGame {
+    board: Board,
+    to_move: Color,
+    transcript: Transcript,
+    black: Option<Sender<String>>,
+    white: Option<Sender<String>>,
+    spectators: Vec<(usize, Sender<String>)>,
+}
+
+impl NetGame {
+    fn started(&self) -> bool {
+        self.black.is_some() && self.white.is_some()
+    }
+
+    // Sends a line to both players and every spectator. Whoever is gone misses it, and
+    // is dealt with when their connection ends.
+    fn broadcast(&self, line: &str) {
+        let players = self.black.iter().chain(self.white.iter());
+        for sender in players.chain(self.spectators.iter().map(|(_, sender)| sender)) {
+            let _ = sender.send(line.to_string());
+        }
+    }
+
+    fn broadcast_board(&self) {
+        self.broadcast(&format!("BOARD {}", Position::new(&self.board, self.to_move)));
+    }
+}
+
+// The winner, the discs of both sides and why the game ended, as in "Black 40-24 resign"
+fn result(game: &NetGame, winner: Option<Color>, reason: &str) -> String {
+    format!(
+        "{} {}-{} {}",
+        winner.map_or(String::from("draw"), |color| format!("{:?}", color)),
+        game.board.num_of_color(Color::Black),
+        game.board.num_of_color(Color::White),
+        reason
+    )
+}
+
+// Every game being hosted, and where finished ones go
+struct Lobby {
+    games: BTreeMap<u32, NetGame>,
+    next_id: u32,
+    save: Option<String>,
+}
+
+// What a client is doing: which game it is in, and as what
+type Seat = Option<(u32, Role)>;
+
+impl Lobby {
+    fn game_of(&mut self, seat: &mut Seat) -> Result<(u32, Role, &mut NetGame), String> {
+        if let Some((id, role)) = *seat {
+            if let Some(game) = self.games.get_mut(&id) {
+                return Ok((id, role, game));
+            }
+        }
+        // the game may have ended since
+        *seat = None;
+        Err(String::from("not in a game"))
+    }
+
+    // Carries out one command from a client. Returns false once the client is done.
+    fn command(&mut self, client: usize, sender: &Sender<String>, seat: &mut Seat, line: &str) -> Result<bool, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let command = words.first().map_or(String::new(), |w| w.to_uppercase());
+        if ["NEW", "JOIN", "WATCH"].contains(&command.as_str()) && self.game_of(seat).is_ok() {
+            return Err(String::from("already in a game"));
+        }
+        let id = || words.get(1).and_then(|id| id.parse::<u32>().ok()).ok_or_else(|| String::from("which game?"));
+        match command.as_str() {
+            "NEW" => {
+                let id = self.next_id;
+                self.next_id += 1;
+                self.games.insert(id, NetGame {
+                    board: Board::new_start(),
+                    to_move: Color::Black,
+                    transcript: Transcript::new(),
+                    black: Some(sender.clone()),
+                    white: None,
+                    spectators: vec![],
+                });
+                *seat = Some((id, Role::Player(Color::Black)));
+                let _ = sender.send(format!("GAME {} Black", id));
+            }
+            "JOIN" => {
+                let id = id()?;
+                let game = self.games.get_mut(&id).ok_or_else(|| format!("no game {}", id))?;
+                if game.white.is_some() {
+                    return Err(format!("game {} has both players", id));
+                }
+                game.white = Some(sender.clone());
+                *seat = Some((id, Role::Player(Color::White)));
+                let _ = sender.send(format!("GAME {} White", id));
+                game.broadcast(&format!("START {}", id));
+                game.broadcast_board();
+                game.broadcast(&format!("TURN {:?}", game.to_move));
+            }
+            "WATCH" => {
+                let id = id()?;
+                let game = self.games.get_mut(&id).ok_or_else(|| format!("no game {}", id))?;
+                game.spectators.push((client, sender.clone()));
+                *seat = Some((id, Role::Spectator));
+                let _ = sender.send(format!("GAME {} watching", id));
+                let _ = sender.send(format!("BOARD {}", Position::new(&game.board, game.to_move)));
+            }
+            "LIST" => {
+                let games: Vec<String> = self
+                    .games
+                    .iter()
+                    .map(|(id, game)| format!("{}:{}", id, if game.started() { "playing" } else { "waiting" }))
+                    .collect();
+                let _ = sender.send(format!("GAMES {}", games.join(" ")).trim_end().to_string());
+            }
+            "MOVE" => {
+                let (id, role, game) = self.game_of(seat)?;
+                let color = match role {
+                    Role::Player(color) => color,
+                    Role::Spectator => return Err(String::from("spectators don't move")),
+                };
+                if !game.started() {
+                    return Err(String::from("waiting for an opponent"));
+                }
+                if game.to_move != color {
+                    return Err(format!("it is {:?}'s turn", game.to_move));
+                }
+                let mv = words.get(1).and_then(|mv| Pos2D::parse(mv)).filter(|&mv| game.board.can_place(mv, color));
+                let mv = mv.ok_or_else(|| format!("not a legal move for {:?}", color))?;
+                game.board.place(mv, color);
+                game.transcript.add(mv, color);
+                game.broadcast(&format!("MOVED {:?} {}", color, mv));
+                if game.board.has_any_moves(color.opposite()) {
+                    game.to_move = color.opposite();
+                } else if game.board.has_any_moves(color) {
+                    game.broadcast(&format!("PASS {:?}", color.opposite()));
+                } else {
+                    game.broadcast_board();
+                    self.finish(id, None, "end");
+                    return Ok(true);
+                }
+                game.broadcast_board();
+                game.broadcast(&format!("TURN {:?}", game.to_move));
+            }
+            "RESIGN" => {
+                let (id, role, started) = self.game_of(seat).map(|(id, role, game)| (id, role, game.started()))?;
+                match role {
+                    Role::Player(color) if started => self.finish(id, Some(color.opposite()), "resign"),
+                    Role::Player(_) => return Err(String::from("the game has not started")),
+                    Role::Spectator => return Err(String::from("spectators can't resign")),
+                }
+            }
+            "QUIT" => return Ok(false),
+            _ => return Err(format!("unknown command, try {}", PROTOCOL)),
+        }
+        Ok(true)
+    }
+
+    // The client is gone. A game it was playing is lost, or dropped if it never started.
+    fn leave(&mut self, client: usize, seat: &mut Seat) {
+        let (id, role, started) = match self.game_of(seat) {
+            Ok((id, role, game)) => (id, role, game.started()),
+            Err(_) => return,
+        };
+        match role {
+            Role::Player(color) if started => self.finish(id, Some(color.opposite()), "disconnect"),
+            Role::Player(_) => {
+                if let Some(game) = self.games.remove(&id) {
+                    game.broadcast(&format!("OVER {}", result(&game, None, "abandoned")));
+                }
+            }
+            Role::Spectator => {
+                if let Some(game) = self.games.get_mut(&id) {
+                    game.spectators.retain(|&(c, _)| c != client);
+                }
+            }
+        }
+    }
+
+    // Ends the game, with the winner if it was decided other than on the board, and saves it
+    fn finish(&mut self, id: u32, winner: Option<Color>, reason: &str) {
+        let game = match self.games.remove(&id) {
+            Some(game) => game,
+            None => return,
+        };
+        let winner = winner.or_else(|| match final_score(&game.board, Color::Black) {
+            score if score > 0 => Some(Color::Black),
+            score if score < 0 => Some(Color::White),
+            _ => None,
+        });
+        let result = result(&game, winner, reason);
+        game.broadcast(&format!("OVER {}", result));
+        if let Some(path) = &self.save {
+            // one game per line, as analyze and the other commands read them
+            let line = format!("# game {}: {}\n{}\n", id, result, game.transcript);
+            let saved = OpenOptions::new().create(true).append(true).open(path).and_then(|mut f| f.write_all(line.as_bytes()));
+            if let Err(e) = saved {
+                eprintln!("Error: cannot save game {} to {}: {}", id, path, e);
+            }
+        }
+    }
+}
+
+// Hosts games between clients that connect over TCP, each on a thread of its own
+pub struct GameServer {
+    listener: TcpListener,
+    lobby: Arc<Mutex<Lobby>>,
+}
+
+impl GameServer {
+    // Finished games are appended to `save`, if given
+    pub fn bind(address: &str, save: Option<String>) -> Result<GameServer, String> {
+        let listener = TcpListener::bind(address).map_err(|e| format!("cannot listen on {}: {}", address, e))?;
+        let lobby = Lobby { games: BTreeMap::new(), next_id: 1, save };
+        Ok(GameServer { listener, lobby: Arc::new(Mutex::new(lobby)) })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.listener.local_addr().unwrap()
+    }
+
+    // Serves until the process ends
+    pub fn run(&self) {
+        for (client, stream) in self.listener.incoming().flatten().enumerate() {
+            let lobby = self.lobby.clone();
+            thread::spawn(move || serve_client(client, stream, &lobby));
+        }
+    }
+}
+
+fn serve_client(client: usize, stream: TcpStream, lobby: &Mutex<Lobby>) {
+    // Lines go out through a thread of their own, so that a slow client never holds up the lobby
+    let (sender, receiver) = mpsc::channel::<String>();
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(_) => return,
+    };
+    thread::spawn(move || {
+        for line in receiver {
+            if writeln!(writer, "{}", line).is_err() {
+                break;
+            }
+        }
+    });
+    let _ = sender.send(format!("WELCOME {}", PROTOCOL));
+
+    let mut seat: Seat = None;
+    for line in BufReader::new(&stream).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match lobby.lock().unwrap().command(client, &sender, &mut seat, line.trim()) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => {
+                let _ = sender.send(format!("ERROR {}", e));
+            }
+        }
+    }
+    lobby.lock().unwrap().leave(client, &mut seat);
+    let _ = stream.shutdown(std::net::Shutdown::Read);
+}
+
+pub struct HostOptions {
+    pub port: u16,
+    // listen on every network interface, not only on this machine
+    pub public: bool,
+    pub save: String,
+}
+
+impl Default for HostOptions {
+    fn default() -> HostOptions {
+        HostOptions { port: 7878, public: false, save: String::from("netgames.txt") }
+    }
+}
+
+pub fn run_host(options: &HostOptions) -> Result<(), String> {
+    let host = if options.public { "0.0.0.0" } else { "127.0.0.1" };
+    let server = GameServer::bind(&format!("{}:{}", host, options.port), Some(options.save.clone()))?;
+    println!("Hosting games on {}, saving finished ones to {}", server.local_addr(), options.save);
+    server.run();
+    Ok(())
+}
+
+pub struct ConnectOptions {
+    pub address: String,
+    pub renderer: Renderer,
+}
+
+// A terminal client: draws the boards the server sends and passes on what is typed, where
+// a move on its own is short for MOVE
+pub fn run_connect(options: &ConnectOptions) -> Result<(), String> {
+    let stream = TcpStream::connect(&options.address).map_err(|e| format!("cannot connect to {}: {}", options.address, e))?;
+    let reader = stream.try_clone().map_err(|e| e.to_string())?;
+    let renderer = options.renderer.clone();
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            match line.strip_prefix("BOARD ").map(|p| p.parse::<Position>()) {
+                Some(Ok(position)) => {
+                    renderer.print(&position.board, &Highlights { to_move: Some(position.to_move), ..Highlights::default() })
+                }
+                _ => println!("{}", line),
+            }
+        }
+        println!("Disconnected");
+        std::process::exit(0);
+    });
+    let mut writer = stream;
+    for line in io::stdin().lock().lines().map_while(Result::ok) {
+        let line = match Pos2D::parse(&line) {
+            Some(mv) => format!("MOVE {}", mv),
+            None => line,
+        };
+        writeln!(writer, "{}", line).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod test {
+
+    use std::fs;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{SocketAddr, TcpStream};
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::netgame::*;
+
+    fn start_server(save: Option<String>) -> SocketAddr {
+        let server = GameServer::bind("127.0.0.1:0", save).unwrap();
+        let address = server.local_addr();
+        thread::spawn(move || server.run());
+        address
+    }
+
+    struct Client {
+        stream: TcpStream,
+        reader: BufReader<TcpStream>,
+    }
+
+    impl Client {
+        fn connect(address: SocketAddr) -> Client {
+            let stream = TcpStream::connect(address).unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+            let reader = BufReader::new(stream.try_clone().unwrap());
+            let mut client = Client { stream, reader };
+            assert!(client.read().starts_with("WELCOME"));
+            client
+        }
+
+        fn send(&mut self, line: &str) {
+            writeln!(self.stream, "{}", line).unwrap();
+        }
+
+        fn read(&mut self) -> String {
+            let mut line = String::new();
+            self.reader.read_line(&mut line).unwrap();
+            line.trim_end().to_string()
+        }
+
+        // Reads up to and including the first line that starts with `prefix`
+        fn read_until(&mut self, prefix: &str) -> String {
+            loop {
+                let line = self.read();
+                assert!(!line.is_empty(), "disconnected while waiting for {}", prefix);
+                if line.starts_with(prefix) {
+                    return line;
+                }
+            }
+        }
+    }
+
+    // Black starts game 1 and White joins it
+    fn start_game(address: SocketAddr) -> (Client, Client) {
+        let mut black = Client::connect(address);
+        black.send("NEW");
+        assert_eq!(black.read(), "GAME 1 Black");
+        let mut white = Client::connect(address);
+        white.send("JOIN 1");
+        assert_eq!(white.read(), "GAME 1 White");
+        for client in [&mut black, &mut white].iter_mut() {
+            assert_eq!(client.read(), "START 1");
+            assert!(client.read().starts_with("BOARD "));
+            assert_eq!(client.read(), "TURN Black");
+        }
+        (black, white)
+    }
+
+    #[test]
+    fn test_moves_reach_players_and_spectators() {
+        let address = start_server(None);
+        let (mut black, mut white) = start_game(address);
+        let mut spectator = Client::connect(address);
+        spectator.send("LIST");
+        assert_eq!(spectator.read(), "GAMES 1:playing");
+        spectator.send("WATCH 1");
+        assert_eq!(spectator.read(), "GAME 1 watching");
+        assert!(spectator.read().starts_with("BOARD "));
+
+        black.send("MOVE c4");
+        for client in [&mut black, &mut white, &mut spectator].iter_mut() {
+            assert_eq!(client.read(), "MOVED Black c4");
+            assert!(client.read().starts_with("BOARD "));
+            assert_eq!(client.read(), "TURN White");
+        }
+    }
+
+    #[test]
+    fn test_illegal_moves_are_refused() {
+        let address = start_server(None);
+        let (mut black, mut white) = start_game(address);
+        white.send("MOVE c3");
+        assert_eq!(white.read(), "ERROR it is Black's turn");
+        black.send("MOVE a0");
+        assert_eq!(black.read(), "ERROR not a legal move for Black");
+        black.send("JOIN 1");
+        assert_eq!(black.read(), "ERROR already in a game");
+        black.send("DANCE");
+        assert!(black.read().starts_with("ERROR unknown command"));
+        // nothing was played, so Black is still to move
+        black.send("MOVE c4");
+        assert_eq!(white.read(), "MOVED Black c4");
+    }
+
+    #[test]
+    fn test_resigned_games_are_saved() {
+        let path = std::env::temp_dir().join(format!("netgame-test-{}.txt", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let address = start_server(Some(path.to_str().unwrap().to_string()));
+        let (mut black, mut white) = start_game(address);
+        black.send("MOVE c4");
+        white.read_until("TURN");
+        white.send("RESIGN");
+        assert_eq!(black.read_until("OVER"), "OVER Black 4-1 resign");
+        assert_eq!(white.read_until("OVER"), "OVER Black 4-1 resign");
+
+        // the game is gone, and both clients can start another
+        white.send("LIST");
+        assert_eq!(white.read(), "GAMES");
+        let saved = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(saved, "# game 1: Black 4-1 resign\nbc4\n");
+    }
+
+    #[test]
+    fn test_leaving_forfeits_the_game() {
+        let address = start_server(None);
+        let (black, mut white) = start_game(address);
+        drop(black);
+        assert_eq!(white.read(), "OVER White 2-2 disconnect");
+
+        // a game nobody joined is dropped
+        let mut waiting = Client::connect(address);
+        waiting.send("NEW");
+        assert_eq!(waiting.read(), "GAME 2 Black");
+        waiting.send("QUIT");
+        assert_eq!(waiting.read(), "OVER draw 2-2 abandoned");
+        let mut other = Client::connect(address);
+        other.send("JOIN 2");
+        assert_eq!(other.read(), "ERROR no game 2");
+    }
+}
@@ -0,0 +1,107 @@
+#![allow(dead_code)]
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::str::FromStr;
+
+use crate::board::*;
+use crate::color::Color;
+use crate::position::Position;
+use crate::selfplay::random_opening;
+use crate::transcript::*;
+
+pub const EVEN_OPENINGS: &str = include_str!("openings.txt");
+
+// The built-in even openings, each one a transcript of eight moves from the starting
+// position that the engine's deeper searches score close to even
+pub fn even_openings() -> Vec<Transcript> {
+    EVEN_OPENINGS
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(Transcript::from_trace)
+        .collect()
+}
+
+// The parallel start: both of a player's discs side by side on the same row
+pub fn cross_board(size: usize) -> Board {
+    let mut board = Board::new_sized(size);
+    let h = size / 2;
+    board.set_at(Pos2D::new(h - 1, h - 1), Color::Black);
+    board.set_at(Pos2D::new(h, h - 1), Color::Black);
+    board.set_at(Pos2D::new(h - 1, h), Color::White);
+    board.set_at(Pos2D::new(h, h), Color::White);
+    board
+}
+
+// Where a game begins
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum Start {
+    #[default]
+    Standard,
+    Cross,
+    // one of the even openings, by its index in the list or picked at random
+    Even(Option<usize>),
+}
+
+impl Start {
+    pub fn position<R: Rng>(&self, size: usize, rng: &mut R) -> Result<Position, String> {
+        match self {
+            Start::Standard => Ok(Position::start_sized(size)),
+            Start::Cross => Ok(Position::new(&cross_board(size), Color::Black)),
+            Start::Even(index) => {
+                if size != 8 {
+                    return Err(String::from("the even openings are for the 8x8 board only"));
+                }
+                let openings = even_openings();
+                let opening = match index {
+                    Some(index) => openings
+                        .get(*index)
+                        .ok_or_else(|| format!("there are only {} even openings", openings.len()))?,
+                    None => openings.choose(rng).unwrap(),
+                };
+                let mut board = Board::new_start();
+                let last_mover = board.replay_transcript(opening);
+                Ok(Position::new(&board, last_mover.opposite()))
+            }
+        }
+    }
+}
+
+impl FromStr for Start {
+    type Err = String;
+
+    // "standard", "cross", "even" for a random even opening or "even:<n>" for a given one
+    fn from_str(s: &str) -> Result<Start, String> {
+        match s.trim().to_lowercase().as_str() {
+            "standard" => Ok(Start::Standard),
+            "cross" | "parallel" => Ok(Start::Cross),
+            "even" => Ok(Start::Even(None)),
+            other => match other.strip_prefix("even:").map(|n| n.parse()) {
+                Some(Ok(index)) => Ok(Start::Even(Some(index))),
+                _ => Err(format!("unknown start '{}'", s)),
+            },
+        }
+    }
+}
+
+// How self-play games begin, so that they don't all follow the same line
+pub enum Openings {
+    // this many random moves from the starting position
+    Random(usize),
+    // a random one of the even openings
+    Even,
+}
+
+impl Openings {
+    pub fn pick<R: Rng>(&self, rng: &mut R) -> Transcript {
+        match self {
+            Openings::Random(plies) => random_opening(*plies, rng),
+            Openings::Even => {
+                let mut openings = even_openings();
+                let index = rng.gen_range(0, openings.len());
+                openings.swap_remove(index)
+            }
+        }
+    }
+}
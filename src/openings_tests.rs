@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod test {
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::board::*;
+    use crate::color::*;
+    use crate::openings::*;
+    use crate::params::EvalParams;
+    use crate::position::*;
+    use crate::selfplay::*;
+    use crate::setup::validate_position;
+    use crate::symmetry::*;
+    use crate::transcript::*;
+
+    #[test]
+    fn test_even_openings() {
+        let openings = even_openings();
+        assert!(openings.len() >= 50);
+        let mut seen = PositionMap::new();
+        for opening in &openings {
+            assert_eq!(opening.moves.len(), 8);
+            let mut b = Board::new_start();
+            assert_eq!(b.replay_transcript(opening), Color::White);
+            assert!(seen.insert_new(&Position::new(&b, Color::Black), ()), "{}", opening);
+        }
+    }
+
+    #[test]
+    fn test_start_positions() {
+        let mut rng = StdRng::seed_from_u64(37);
+        assert_eq!("cross".parse::<Start>().unwrap(), Start::Cross);
+        assert_eq!("even:3".parse::<Start>().unwrap(), Start::Even(Some(3)));
+        assert!("even:x".parse::<Start>().is_err());
+
+        let cross = Start::Cross.position(8, &mut rng).unwrap();
+        assert!(validate_position(&cross.board, cross.to_move).unwrap().is_empty());
+        assert_ne!(cross.to_string(), Position::start().to_string());
+
+        let even = Start::Even(Some(0)).position(8, &mut rng).unwrap();
+        assert_eq!(even.board.num_occupied(), 12);
+        assert_eq!(even.to_move, Color::Black);
+        assert!(Start::Even(Some(100000)).position(8, &mut rng).is_err());
+        assert!(Start::Even(None).position(6, &mut rng).is_err());
+        assert_eq!(Start::Standard.position(6, &mut rng).unwrap().board.size(), 6);
+    }
+
+    #[test]
+    fn test_transcript_records_start() {
+        let mut rng = StdRng::seed_from_u64(38);
+        let start = Start::Cross.position(8, &mut rng).unwrap();
+        let mut transcript = Transcript::starting_at(&start);
+        let mv = start.board.get_available_moves_for(Color::Black).next().unwrap();
+        transcript.add(mv, Color::Black);
+
+        let parsed = Transcript::parse(&transcript.to_string()).unwrap();
+        assert_eq!(parsed.start.unwrap().to_string(), start.to_string());
+        assert_eq!(parsed.moves.len(), 1);
+        assert_eq!(Transcript::starting_at(&Position::start()).to_string(), "");
+
+        // Self-play continues from the recorded start
+        let (played, board) = play_out(&transcript, &EvalParams::default(), &EvalParams::default(), 1).unwrap();
+        assert_eq!(played.start.as_ref().unwrap().to_string(), start.to_string());
+        let mut replayed = played.start_board();
+        replayed.replay_transcript(&played);
+        assert_eq!(replayed.to_string(), board.to_string());
+    }
+}
@@ -0,0 +1,422 @@
+#![allow(dead_code)]
+
+use std::slice::Iter;
+use std::str::FromStr;
+
+use crate::board::{is_valid_size, Variant};
+use crate::clock::TimeControl;
+use crate::color::Color;
+use crate::ffo::FfoOptions;
+use crate::netgame::{ConnectOptions, HostOptions};
+use crate::openings::Start;
+use crate::params::EvalParams;
+use crate::perft::PerftOptions;
+use crate::player::*;
+use crate::ponder::Ponder;
+use crate::position::Position;
+use crate::puzzle::PuzzleOptions;
+use crate::render::Renderer;
+use crate::review::AnalyzeOptions;
+use crate::server::ServeOptions;
+use crate::solve::SolveOptions;
+use crate::svg::{DiagramOptions, Overlay};
+use crate::tune::TuneOptions;
+
+pub const USAGE: &str = "\
+Usage: reversi [command] [options]
+
+Positions are written as the 64 cells row by row from a1, X for Black, O for White and
+- for empty, followed by X or O for the side to move. Instead of a position, a file
+containing one can be given. Positions on other board sizes have 16, 36 or 100 cells,
+and # marks a blocked cell, where no disc can go.
+
+Commands:
+  play                   play against the engine (the default)
+  tui                    like play, but full-screen, with the keyboard or mouse
+  setup [position]       edit a position, then play from it
+  tune                   tune the evaluation weights on self-play games
+  analyze <transcript>   review every move of a game; the transcript can also be a file
+  perft <depth>          count the positions reachable in depth plies from the start
+  ffo                    solve the built-in FFO endgame test positions, #40 to #43, and check
+                         the results
+  solve <position>       find the best move and the exact final score of a position
+  diagram <input>        draw a position, or a transcript after some of its moves, as SVG
+  puzzles                find puzzles in self-play or imported games, positions where one move
+                         alone wins or is far better than the rest, to solve with the puzzle
+                         command while playing
+  serve                  answer HTTP requests from this machine with JSON, see below
+  host                   host games between people connecting over the network, see below
+  connect <host:port>    play or watch games on a host; a move on its own plays it, and
+                         NEW, JOIN <id>, WATCH <id>, LIST, RESIGN and QUIT go to the host
+
+Options:
+  --position <position>  start from this position instead (play, analyze and perft)
+  --size <n>             play or count on an n x n board: 4, 6, 8 or 10 (play and perft)
+  --variant <name>       normal, or anti for anti-reversi, where fewer discs win (play and solve)
+  --start <name>         play from the standard start, the cross (parallel) start, a random
+                         one of the built-in even openings with even, or a given one with even:<n>
+  --black <player>       who plays Black: human, engine, engine:<depth> or a level (human)
+  --white <player>       who plays White (engine, searching 6 moves deep)
+  --black-params <file>  evaluation parameters for the engine playing Black
+  --white-params <file>  evaluation parameters for the engine playing White
+  --ponder <mode>        let the engine think while the human is to move: about its answer to
+                         the expected reply with guess, or to every reply with all (off); a
+                         search that finished in time saves the engine its own, and the
+                         positions searched speed up the engine's search in any case
+  --seed <n>             seed for the engine's random choices and a random start, to replay a game
+  --book <file>          opening book of games, one transcript per line (the engine's own lines)
+  --tutor                explain the hint, and warn about a move that loses a lot before playing
+                         it, saying why and what is better (also the tutor command while playing)
+  --pause <ms|enter>     wait this long, or for Enter, after each move when engines play each other
+  --time <control>       a clock for each side: <minutes> for the game, <minutes>+<seconds> with
+                         an increment per move, or move:<seconds> per move (none); the engine
+                         searches less deep when its time runs short
+  --lose-on-time         a side that runs out of time loses, instead of just being shown late
+  --board <style>        how to draw the board: grid, ansi for colored discs or ascii (play and
+                         analyze, where it also draws the positions after mistakes and blunders)
+  --standard-labels      number the rows from 1 as in standard notation, rather than from 0
+                         (analyze; diagram does so by default; play keeps the rows that moves
+                         are typed with)
+  --white-view           draw the board turned around, as White sees it
+  --no-markers           don't mark legal moves, the last move and the discs it turned over
+  --eval-params <file>   load evaluation parameters from a TOML or JSON file
+  --eval <name=value>    override one evaluation parameter, e.g. --eval midgame.corner=120
+  --help                 show this message
+
+Levels, for --black, --white and the level command:
+  beginner               looks 1 move ahead, misjudges moves and often plays a worse one
+  easy                   2 moves ahead, with the opening book and some errors
+  medium                 3 moves ahead, with the opening book and a few errors
+  hard                   5 moves ahead, with the opening book
+  expert                 8 moves ahead, with the opening book, at most 5 seconds a move
+
+Tune options:
+  --games <n>            self-play games to take positions from (200)
+  --transcripts <file>   also take positions from the games in this file, one transcript per line
+  --exact <n>            label positions with at most n empty cells by exact search (0)
+  --depth <n>            search depth for self-play and the verification match (1)
+  --random-plies <n>     random moves at the start of each game (8)
+  --even-openings        start each game from a random one of the built-in even openings instead
+  --match <n>            pairs of games in the before/after match (20)
+  --seed <n>             seed for the random moves
+  --out <file>           where to write the tuned parameters (tuned.toml)
+
+Analyze options:
+  --depth <n>            search depth for every position (3)
+  --json <file>          also write the review as JSON; \"-\" prints only the JSON
+  --inaccuracy <n>       score loss from which a move is an inaccuracy (10)
+  --mistake <n>          score loss from which a move is a mistake (50)
+  --blunder <n>          score loss from which a move is a blunder (100)
+
+Diagram options:
+  --ply <n>              show the game after its first n moves (all of them)
+  --numbers              number the moves of the game on the discs
+  --legal                mark the moves of the side to move
+  --scores <depth>       write on every move of the side to move its score from a search of depth
+  --out <file>           write the SVG to a file instead of standard output
+  --cell-size <n>        width of a cell in pixels (40)
+  --no-coordinates       leave out the letters and numbers around the board
+  --engine-labels        number the rows from 0 like the engine's moves, rather than from 1
+
+Puzzles options:
+  --games <n>            self-play games to look for puzzles in (20)
+  --transcripts <file>   also look in the games in this file, one transcript per line
+  --empties <n>          look at positions with at most n empty cells, solving every move (12)
+  --margin <n>           discs by which the solution must beat every other move, unless it is
+                         the only one that wins (10)
+  --depth <n>            search depth for self-play (2)
+  --random-plies <n>     random moves at the start of each game (8)
+  --seed <n>             seed for the random moves
+  --out <file>           where to write the puzzles (puzzles.txt)
+
+Serve options:
+  --port <n>             port to listen on (8080)
+  Every request is a POST with a JSON body. The game is given as \"position\" or as
+  \"transcript\", the usual start without either. A request may search for \"time_ms\"
+  (10 seconds, at most 60); /analyze and /review fail when that is not enough.
+  /moves                 the legal moves and the disc count
+  /play                  plays \"move\" and returns the game after it
+  /best                  the best move, searching up to \"depth\" moves deep (6) for as long as
+                         \"time_ms\" allows
+  /analyze               the best \"lines\" moves (3) with their lines of play, at \"depth\" (3)
+  /review                every move of the \"transcript\" reviewed as by analyze, at \"depth\" (3)
+
+Host options:
+  --port <n>             port to listen on (7878)
+  --public               accept players from other machines, not only from this one
+  --save <file>          where finished games are added, one transcript per line (netgames.txt)
+  Clients send one command per line: NEW, JOIN <id>, WATCH <id>, LIST, MOVE <move>, RESIGN
+  or QUIT. The host checks every move and sends BOARD, MOVED, PASS, TURN and OVER lines to
+  both players and to everyone watching.
+
+Connect options:
+  --board <style>        how to draw the board: grid, ansi or ascii
+
+Perft options:
+  --divide               show the count for every first move separately
+
+FFO options:
+  --first <n>            first position to solve (40)
+  --last <n>             last position to solve (43)
+  --file <file>          read the positions from a file in the format of src/ffo.txt, e.g. to
+                         solve others of the suite, which are not built in";
+
+pub struct PlayOptions {
+    // a position to start from, which wins over `start`
+    pub position: Option<Position>,
+    pub start: Start,
+    pub size: usize,
+    // start by editing the position
+    pub setup: bool,
+    // full-screen instead of at the prompt
+    pub tui: bool,
+    pub variant: Variant,
+    // how the board is shown at the prompt
+    pub renderer: Renderer,
+    pub players: Players,
+    // between engine moves when no human is playing
+    pub pause: Pause,
+    pub time: TimeControl,
+    // running out of time loses the game, rather than just showing on the clock
+    pub lose_on_time: bool,
+    pub ponder: Ponder,
+    // seed for the engine's random choices and the random start, so a game can be replayed
+    pub seed: Option<u64>,
+    // opening book file, instead of the built-in one
+    pub book: Option<String>,
+    // explain the hint and question moves that lose a lot
+    pub tutor: bool,
+}
+
+impl Default for PlayOptions {
+    fn default() -> PlayOptions {
+        PlayOptions {
+            position: None,
+            start: Start::Standard,
+            size: 8,
+            setup: false,
+            tui: false,
+            variant: Variant::Normal,
+            renderer: Renderer::default(),
+            players: Players::default(),
+            pause: Pause::None,
+            time: TimeControl::Unlimited,
+            lose_on_time: false,
+            ponder: Ponder::Off,
+            seed: None,
+            book: None,
+            tutor: false,
+        }
+    }
+}
+
+pub enum Command {
+    Play(Box<PlayOptions>),
+    Tune(TuneOptions),
+    Analyze(AnalyzeOptions),
+    Perft(PerftOptions),
+    Ffo(FfoOptions),
+    Solve(SolveOptions),
+    Diagram(DiagramOptions),
+    Puzzles(PuzzleOptions),
+    Serve(ServeOptions),
+    Host(HostOptions),
+    Connect(ConnectOptions),
+}
+
+pub struct Options {
+    pub command: Command,
+    pub eval_params: EvalParams,
+}
+
+impl Options {
+    pub fn from_args(args: &[String]) -> Result<Options, String> {
+        let mut args = args.iter();
+        let mut command = match args.as_slice().first() {
+            Some(name) if !name.starts_with("--") => {
+                args.next();
+                match name.as_str() {
+                    "play" => Command::Play(Box::default()),
+                    "setup" => Command::Play(Box::new(PlayOptions { setup: true, ..PlayOptions::default() })),
+                    "tui" => Command::Play(Box::new(PlayOptions { tui: true, ..PlayOptions::default() })),
+                    "tune" => Command::Tune(TuneOptions::default()),
+                    "analyze" => Command::Analyze(AnalyzeOptions::default()),
+                    "perft" => {
+                        let depth = args.next().ok_or("perft needs a depth")?;
+                        let depth = depth.parse().map_err(|_| format!("bad perft depth '{}'", depth))?;
+                        Command::Perft(PerftOptions { depth, ..PerftOptions::default() })
+                    }
+                    "ffo" => Command::Ffo(FfoOptions::default()),
+                    "puzzles" => Command::Puzzles(PuzzleOptions::default()),
+                    "serve" => Command::Serve(ServeOptions::default()),
+                    "host" => Command::Host(HostOptions::default()),
+                    "connect" => {
+                        let address = args.next().ok_or("connect needs the address of a host")?;
+                        Command::Connect(ConnectOptions { address: address.clone(), renderer: Renderer::default() })
+                    }
+                    "diagram" => {
+                        let input = args.next().ok_or("diagram needs a position or a transcript")?;
+                        Command::Diagram(DiagramOptions { input: input.clone(), ..DiagramOptions::default() })
+                    }
+                    "solve" => {
+                        let position = args.next().ok_or("solve needs a position")?;
+                        Command::Solve(SolveOptions {
+                            position: Position::from_arg(position)?,
+                            variant: Variant::Normal,
+                        })
+                    }
+                    _ => return Err(format!("unknown command '{}'", name)),
+                }
+            }
+            _ => Command::Play(Box::default()),
+        };
+
+        let mut eval_params = EvalParams::default();
+        let mut overrides = vec![];
+        let mut engine_params = vec![];
+        while let Some(arg) = args.next() {
+            match (arg.as_str(), &mut command) {
+                ("--eval-params", _) => eval_params = EvalParams::load(value_of(arg, &mut args)?)?,
+                ("--eval", _) => overrides.push(value_of(arg, &mut args)?),
+                ("--position", Command::Play(play)) => play.position = Some(position_of(arg, &mut args)?),
+                ("--position", Command::Analyze(analyze)) => analyze.position = Some(position_of(arg, &mut args)?),
+                ("--position", Command::Perft(perft)) => perft.position = Some(position_of(arg, &mut args)?),
+                ("--black", Command::Play(play)) => play.players.black = parse_value(arg, &mut args)?,
+                ("--white", Command::Play(play)) => play.players.white = parse_value(arg, &mut args)?,
+                ("--black-params", Command::Play(_)) => engine_params.push((Color::Black, value_of(arg, &mut args)?)),
+                ("--white-params", Command::Play(_)) => engine_params.push((Color::White, value_of(arg, &mut args)?)),
+                ("--time", Command::Play(play)) => play.time = parse_value(arg, &mut args)?,
+                ("--lose-on-time", Command::Play(play)) => play.lose_on_time = true,
+                ("--ponder", Command::Play(play)) => play.ponder = parse_value(arg, &mut args)?,
+                ("--pause", Command::Play(play)) => play.pause = parse_value(arg, &mut args)?,
+                ("--seed", Command::Play(play)) => play.seed = Some(parse_value(arg, &mut args)?),
+                ("--tutor", Command::Play(play)) => play.tutor = true,
+                ("--book", Command::Play(play)) => play.book = Some(value_of(arg, &mut args)?.to_string()),
+                ("--size", Command::Play(play)) => play.size = size_of(arg, &mut args)?,
+                ("--start", Command::Play(play)) => play.start = parse_value(arg, &mut args)?,
+                ("--size", Command::Perft(perft)) => {
+                    perft.position = Some(Position::start_sized(size_of(arg, &mut args)?))
+                }
+                ("--variant", Command::Play(play)) => play.variant = parse_value(arg, &mut args)?,
+                ("--variant", Command::Solve(solve)) => solve.variant = parse_value(arg, &mut args)?,
+                ("--board", Command::Play(play)) => play.renderer.style = parse_value(arg, &mut args)?,
+                ("--board", Command::Analyze(analyze)) => {
+                    analyze.renderer.style = parse_value(arg, &mut args)?;
+                    analyze.diagrams = true;
+                }
+                ("--standard-labels", Command::Analyze(analyze)) => analyze.renderer.standard_labels = true,
+                ("--white-view", Command::Play(play)) => play.renderer.white_view = true,
+                ("--white-view", Command::Analyze(analyze)) => analyze.renderer.white_view = true,
+                ("--no-markers", Command::Play(play)) => play.renderer.markers = false,
+                ("--no-markers", Command::Analyze(analyze)) => analyze.renderer.markers = false,
+                ("--games", Command::Tune(tune)) => tune.games = parse_value(arg, &mut args)?,
+                ("--transcripts", Command::Tune(tune)) => {
+                    tune.transcripts = Some(value_of(arg, &mut args)?.to_string())
+                }
+                ("--exact", Command::Tune(tune)) => tune.exact_empties = parse_value(arg, &mut args)?,
+                ("--depth", Command::Tune(tune)) => tune.depth = depth_of(arg, &mut args)?,
+                ("--random-plies", Command::Tune(tune)) => tune.random_plies = parse_value(arg, &mut args)?,
+                ("--even-openings", Command::Tune(tune)) => tune.even_openings = true,
+                ("--match", Command::Tune(tune)) => tune.match_pairs = parse_value(arg, &mut args)?,
+                ("--seed", Command::Tune(tune)) => tune.seed = Some(parse_value(arg, &mut args)?),
+                ("--out", Command::Tune(tune)) => tune.out = value_of(arg, &mut args)?.to_string(),
+                ("--depth", Command::Analyze(analyze)) => analyze.depth = depth_of(arg, &mut args)?,
+                ("--json", Command::Analyze(analyze)) => analyze.json = Some(value_of(arg, &mut args)?.to_string()),
+                ("--inaccuracy", Command::Analyze(analyze)) => {
+                    analyze.thresholds.inaccuracy = parse_value(arg, &mut args)?
+                }
+                ("--mistake", Command::Analyze(analyze)) => analyze.thresholds.mistake = parse_value(arg, &mut args)?,
+                ("--blunder", Command::Analyze(analyze)) => analyze.thresholds.blunder = parse_value(arg, &mut args)?,
+                ("--ply", Command::Diagram(diagram)) => diagram.ply = Some(parse_value(arg, &mut args)?),
+                ("--numbers", Command::Diagram(diagram)) => diagram.numbers = true,
+                ("--legal", Command::Diagram(diagram)) => diagram.overlay = Overlay::Legal,
+                ("--scores", Command::Diagram(diagram)) => diagram.overlay = Overlay::Eval(depth_of(arg, &mut args)?),
+                ("--out", Command::Diagram(diagram)) => diagram.out = Some(value_of(arg, &mut args)?.to_string()),
+                ("--cell-size", Command::Diagram(diagram)) => diagram.svg.cell_size = parse_value(arg, &mut args)?,
+                ("--no-coordinates", Command::Diagram(diagram)) => diagram.svg.coordinates = false,
+                ("--standard-labels", Command::Diagram(diagram)) => diagram.svg.standard_labels = true,
+                ("--engine-labels", Command::Diagram(diagram)) => diagram.svg.standard_labels = false,
+                ("--games", Command::Puzzles(puzzles)) => puzzles.games = parse_value(arg, &mut args)?,
+                ("--transcripts", Command::Puzzles(puzzles)) => {
+                    puzzles.transcripts = Some(value_of(arg, &mut args)?.to_string())
+                }
+                ("--empties", Command::Puzzles(puzzles)) => puzzles.empties = parse_value(arg, &mut args)?,
+                ("--margin", Command::Puzzles(puzzles)) => puzzles.margin = parse_value(arg, &mut args)?,
+                ("--depth", Command::Puzzles(puzzles)) => puzzles.depth = depth_of(arg, &mut args)?,
+                ("--random-plies", Command::Puzzles(puzzles)) => puzzles.random_plies = parse_value(arg, &mut args)?,
+                ("--seed", Command::Puzzles(puzzles)) => puzzles.seed = Some(parse_value(arg, &mut args)?),
+                ("--out", Command::Puzzles(puzzles)) => puzzles.out = value_of(arg, &mut args)?.to_string(),
+                ("--port", Command::Serve(serve)) => serve.port = parse_value(arg, &mut args)?,
+                ("--port", Command::Host(host)) => host.port = parse_value(arg, &mut args)?,
+                ("--public", Command::Host(host)) => host.public = true,
+                ("--save", Command::Host(host)) => host.save = value_of(arg, &mut args)?.to_string(),
+                ("--board", Command::Connect(connect)) => connect.renderer.style = parse_value(arg, &mut args)?,
+                ("--divide", Command::Perft(perft)) => perft.divide = true,
+                ("--first", Command::Ffo(ffo)) => ffo.first = parse_value(arg, &mut args)?,
+                ("--last", Command::Ffo(ffo)) => ffo.last = parse_value(arg, &mut args)?,
+                ("--file", Command::Ffo(ffo)) => ffo.file = Some(value_of(arg, &mut args)?.to_string()),
+                (_, Command::Play(play)) if play.setup && !arg.starts_with("--") && play.position.is_none() => {
+                    play.position = Some(Position::from_arg(arg)?)
+                }
+                (_, Command::Analyze(analyze)) if !arg.starts_with("--") && analyze.transcript.is_empty() => {
+                    analyze.transcript = arg.clone()
+                }
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+        if let Command::Analyze(analyze) = &command {
+            if analyze.transcript.is_empty() {
+                return Err(String::from("analyze needs a transcript"));
+            }
+        }
+
+        // Overrides win over the file, regardless of the order they were given in
+        for assignment in overrides {
+            eval_params.set(assignment)?;
+        }
+        // The players may come after their parameters
+        if let Command::Play(play) = &mut command {
+            for (color, path) in engine_params {
+                match play.players.of_mut(color) {
+                    Player::Engine(settings) => settings.params = Some(EvalParams::load(path)?),
+                    Player::Human => return Err(format!("{:?} is not played by the engine", color)),
+                }
+            }
+        }
+        Ok(Options { command, eval_params })
+    }
+}
+
+fn value_of<'a>(flag: &str, args: &mut Iter<'a, String>) -> Result<&'a str, String> {
+    args.next()
+        .map(|v| v.as_str())
+        .ok_or_else(|| format!("{} needs a value", flag))
+}
+
+fn position_of(flag: &str, args: &mut Iter<String>) -> Result<Position, String> {
+    Position::from_arg(value_of(flag, args)?)
+}
+
+fn size_of(flag: &str, args: &mut Iter<String>) -> Result<usize, String> {
+    let size = parse_value(flag, args)?;
+    if !is_valid_size(size) {
+        return Err(format!("unsupported board size {}", size));
+    }
+    Ok(size)
+}
+
+// A search depth, which must be at least one move
+fn depth_of(flag: &str, args: &mut Iter<String>) -> Result<i32, String> {
+    let depth = parse_value(flag, args)?;
+    if depth < 1 {
+        return Err(format!("{} must be at least 1", flag));
+    }
+    Ok(depth)
+}
+
+fn parse_value<T: FromStr>(flag: &str, args: &mut Iter<String>) -> Result<T, String> {
+    let value = value_of(flag, args)?;
+    value
+        .parse()
+        .map_err(|_| format!("bad value '{}' for {}", value, flag))
+}
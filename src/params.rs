@@ -0,0 +1,166 @@
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+// Weights of the heuristic terms used by eval before the final disc count takes over
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Weights {
+    pub mobility: i32,
+    pub frontier: i32,
+    pub corner: i32,
+    pub x_square: i32,
+    pub c_square: i32,
+    pub edge_run: i32,
+    pub parity: i32,
+}
+
+impl Default for Weights {
+    fn default() -> Weights {
+        Weights {
+            mobility: 1,
+            frontier: 0,
+            corner: 100,
+            x_square: -100,
+            c_square: 0,
+            edge_run: 50,
+            parity: 0,
+        }
+    }
+}
+
+impl Weights {
+    pub fn to_array(&self) -> [i32; 7] {
+        [
+            self.mobility,
+            self.frontier,
+            self.corner,
+            self.x_square,
+            self.c_square,
+            self.edge_run,
+            self.parity,
+        ]
+    }
+
+    pub fn from_array(values: [i32; 7]) -> Weights {
+        Weights {
+            mobility: values[0],
+            frontier: values[1],
+            corner: values[2],
+            x_square: values[3],
+            c_square: values[4],
+            edge_run: values[5],
+            parity: values[6],
+        }
+    }
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut i32> {
+        match name {
+            "mobility" => Some(&mut self.mobility),
+            "frontier" => Some(&mut self.frontier),
+            "corner" => Some(&mut self.corner),
+            "x_square" => Some(&mut self.x_square),
+            "c_square" => Some(&mut self.c_square),
+            "edge_run" => Some(&mut self.edge_run),
+            "parity" => Some(&mut self.parity),
+            _ => None,
+        }
+    }
+}
+
+// Everything eval needs to know. The game is split into phases by the number of occupied
+// cells: the opening weights apply below midgame_from, the midgame weights below count_from,
+// and from count_from on only the final disc count matters. On boards other than 8x8 the
+// phases go by the number of empty cells instead, see game_phase.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvalParams {
+    pub midgame_from: i32,
+    pub count_from: i32,
+    pub opening: Weights,
+    pub midgame: Weights,
+}
+
+impl Default for EvalParams {
+    fn default() -> EvalParams {
+        EvalParams {
+            midgame_from: 20,
+            count_from: 52,
+            opening: Weights::default(),
+            midgame: Weights::default(),
+        }
+    }
+}
+
+impl EvalParams {
+    pub fn counting_from(count_from: i32) -> EvalParams {
+        EvalParams {
+            count_from,
+            ..EvalParams::default()
+        }
+    }
+
+    pub fn weights_for(&self, occupied: i32) -> &Weights {
+        if occupied < self.midgame_from {
+            &self.opening
+        } else {
+            &self.midgame
+        }
+    }
+
+    // Reads the parameters from a JSON file if the extension says so, TOML otherwise.
+    // Anything missing from the file keeps its default value.
+    pub fn load(path: &str) -> Result<EvalParams, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+        if EvalParams::is_json(path) {
+            serde_json::from_str(&text).map_err(|e| format!("cannot parse {}: {}", path, e))
+        } else {
+            toml::from_str(&text).map_err(|e| format!("cannot parse {}: {}", path, e))
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let text = if EvalParams::is_json(path) {
+            serde_json::to_string_pretty(self).map_err(|e| e.to_string())?
+        } else {
+            toml::to_string(self).map_err(|e| e.to_string())?
+        };
+        fs::write(path, text).map_err(|e| format!("cannot write {}: {}", path, e))
+    }
+
+    fn is_json(path: &str) -> bool {
+        Path::new(path).extension().is_some_and(|ext| ext == "json")
+    }
+
+    // Applies a single "name=value" override, as given on the command line.
+    // Weights are addressed by phase, e.g. "midgame.corner=120"; a weight without
+    // a phase, e.g. "corner=120", is set for both phases.
+    pub fn set(&mut self, assignment: &str) -> Result<(), String> {
+        let mut parts = assignment.splitn(2, '=');
+        let name = parts.next().unwrap().trim();
+        let value = parts
+            .next()
+            .ok_or_else(|| format!("expected name=value, got '{}'", assignment))?
+            .trim()
+            .parse::<i32>()
+            .map_err(|e| format!("bad value in '{}': {}", assignment, e))?;
+
+        let unknown = || format!("unknown evaluation parameter '{}'", name);
+        match name.split_once('.') {
+            Some(("opening", weight)) => *self.opening.field_mut(weight).ok_or_else(unknown)? = value,
+            Some(("midgame", weight)) => *self.midgame.field_mut(weight).ok_or_else(unknown)? = value,
+            Some(_) => return Err(unknown()),
+            None => match name {
+                "midgame_from" => self.midgame_from = value,
+                "count_from" => self.count_from = value,
+                _ => {
+                    *self.opening.field_mut(name).ok_or_else(unknown)? = value;
+                    *self.midgame.field_mut(name).ok_or_else(unknown)? = value;
+                }
+            },
+        }
+        Ok(())
+    }
+}
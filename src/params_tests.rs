@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod test {
+
+    use crate::options::*;
+    use crate::params::*;
+
+    #[test]
+    fn test_set_weights() {
+        let mut params = EvalParams::default();
+        params.set("midgame.corner=120").unwrap();
+        params.set("x_square = -80").unwrap();
+        params.set("count_from=50").unwrap();
+
+        assert_eq!(params.opening.corner, 100);
+        assert_eq!(params.midgame.corner, 120);
+        assert_eq!(params.opening.x_square, -80);
+        assert_eq!(params.midgame.x_square, -80);
+        assert_eq!(params.count_from, 50);
+
+        assert!(params.set("midgame.nonsense=1").is_err());
+        assert!(params.set("endgame.corner=1").is_err());
+        assert!(params.set("corner").is_err());
+        assert!(params.set("corner=lots").is_err());
+    }
+
+    #[test]
+    fn test_weights_by_phase() {
+        let mut params = EvalParams::default();
+        params.set("opening.mobility=5").unwrap();
+        assert_eq!(params.weights_for(params.midgame_from - 1).mobility, 5);
+        assert_eq!(params.weights_for(params.midgame_from).mobility, 1);
+    }
+
+    #[test]
+    fn test_partial_files() {
+        let params: EvalParams = toml::from_str("count_from = 48\n[midgame]\ncorner = 90\n").unwrap();
+        assert_eq!(params.count_from, 48);
+        assert_eq!(params.midgame.corner, 90);
+        assert_eq!(params.midgame.edge_run, 50);
+        assert_eq!(params.opening, Weights::default());
+
+        let params: EvalParams = serde_json::from_str(r#"{"opening": {"parity": 3}}"#).unwrap();
+        assert_eq!(params.opening.parity, 3);
+        assert_eq!(params.count_from, 52);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut params = EvalParams::default();
+        params.set("midgame.frontier=-2").unwrap();
+        for name in &["params_test.toml", "params_test.json"] {
+            let path = std::env::temp_dir().join(name);
+            let path = path.to_str().unwrap();
+            params.save(path).unwrap();
+            assert_eq!(EvalParams::load(path).unwrap(), params);
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_options() {
+        let args: Vec<String> = ["--eval", "midgame.corner=130", "--eval", "count_from=56"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let options = Options::from_args(&args).unwrap();
+        assert_eq!(options.eval_params.midgame.corner, 130);
+        assert_eq!(options.eval_params.count_from, 56);
+
+        assert!(Options::from_args(&["--eval".to_string()]).is_err());
+        assert!(Options::from_args(&["--bogus".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+#![allow(dead_code)]
+
+use std::time::Instant;
+
+use crate::board::*;
+use crate::color::Color;
+use crate::position::Position;
+
+// Counts the leaves of the game tree `depth` plies deep. As in the published perft
+// numbers, a forced pass counts as a ply of its own and a game that ends earlier
+// counts as a single leaf.
+pub fn perft(board: &Board, color: Color, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = board.get_available_moves_for(color).collect::<Vec<_>>();
+    if moves.is_empty() {
+        if board.has_any_moves(color.opposite()) {
+            return perft(board, color.opposite(), depth - 1);
+        }
+        return 1;
+    }
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    moves
+        .iter()
+        .map(|mv| {
+            let mut child = Board::new_from(board);
+            child.place(*mv, color);
+            perft(&child, color.opposite(), depth - 1)
+        })
+        .sum()
+}
+
+// perft split up by the first move, for comparing against another move generator.
+// A forced pass at the root shows up as None; a finished game has no entries.
+pub fn perft_divide(board: &Board, color: Color, depth: u32) -> Vec<(Option<Pos2D>, u64)> {
+    if depth == 0 {
+        return vec![];
+    }
+    let moves = board.get_available_moves_for(color).collect::<Vec<_>>();
+    if moves.is_empty() {
+        if board.has_any_moves(color.opposite()) {
+            return vec![(None, perft(board, color.opposite(), depth - 1))];
+        }
+        return vec![];
+    }
+    moves
+        .iter()
+        .map(|mv| {
+            let mut child = Board::new_from(board);
+            child.place(*mv, color);
+            (Some(*mv), perft(&child, color.opposite(), depth - 1))
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct PerftOptions {
+    pub depth: u32,
+    pub divide: bool,
+    // where to count from, if not from the starting position
+    pub position: Option<Position>,
+}
+
+pub fn run_perft(options: &PerftOptions) {
+    let position = options.position.clone().unwrap_or_else(Position::start);
+    let (board, color) = (&position.board, position.to_move);
+    let start = Instant::now();
+    let nodes = if options.divide {
+        let divided = perft_divide(board, color, options.depth);
+        for (mv, count) in &divided {
+            match mv {
+                Some(mv) => println!("{}: {}", mv, count),
+                None => println!("pass: {}", count),
+            }
+        }
+        divided.iter().map(|(_, count)| count).sum()
+    } else {
+        perft(board, color, options.depth)
+    };
+    let elapsed = start.elapsed();
+    println!(
+        "perft({}) = {}. Elapsed {:?}. Speed: {}Knodes/sec",
+        options.depth,
+        nodes,
+        elapsed,
+        (nodes as f64 / 1000.0 / elapsed.as_secs_f64().max(1e-9)) as u64
+    );
+}
@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod test {
+
+    use crate::board::*;
+    use crate::color::*;
+    use crate::perft::*;
+
+    // Known leaf counts from the standard starting position
+    const INITIAL_PERFT: [u64; 9] = [1, 4, 12, 56, 244, 1396, 8200, 55092, 390216];
+
+    #[test]
+    fn test_perft_initial_position() {
+        let b = Board::new_start();
+        for (depth, expected) in INITIAL_PERFT.iter().enumerate() {
+            assert_eq!(perft(&b, Color::Black, depth as u32), *expected, "depth {}", depth);
+        }
+    }
+
+    #[test]
+    #[ignore] // takes a while without optimizations
+    fn test_perft_initial_position_deep() {
+        let b = Board::new_start();
+        assert_eq!(perft(&b, Color::Black, 9), 3005288);
+        assert_eq!(perft(&b, Color::Black, 10), 24571284);
+    }
+
+    #[test]
+    fn test_perft_divide_adds_up() {
+        let b = Board::new_start();
+        let divided = perft_divide(&b, Color::Black, 5);
+        assert_eq!(divided.len(), 4);
+        assert_eq!(divided.iter().map(|(_, n)| n).sum::<u64>(), perft(&b, Color::Black, 5));
+    }
+
+    fn get_pass_board() -> Board {
+        // Black cannot flank the corner, so it has to pass, and White's c0 ends the game
+        let mut b = Board::new();
+        b.set_at_c('A', 0, Color::White);
+        b.set_at_c('B', 0, Color::Black);
+        b
+    }
+
+    #[test]
+    fn test_perft_counts_passes() {
+        let b = get_pass_board();
+        assert!(!b.has_any_moves(Color::Black));
+        assert_eq!(perft(&b, Color::Black, 1), 1);
+        assert_eq!(perft_divide(&b, Color::Black, 1), vec![(None, 1)]);
+        assert_eq!(perft(&b, Color::Black, 2), 1);
+        assert_eq!(perft(&b, Color::Black, 3), 1);
+        assert_eq!(perft(&b, Color::Black, 10), 1);
+    }
+}
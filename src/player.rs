@@ -0,0 +1,241 @@
+#![allow(dead_code)]
+
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::analysis::*;
+use crate::board::*;
+use crate::book::Book;
+use crate::color::Color;
+use crate::params::EvalParams;
+use crate::position::Position;
+use crate::stat::Stat;
+
+// How the engine plays for one side
+#[derive(Clone, Debug, PartialEq)]
+pub struct EngineSettings {
+    // search depth in moves of both sides, as for negamax
+    pub depth: i32,
+    // evaluation parameters of its own, instead of the ones the game was started with
+    pub params: Option<EvalParams>,
+    // a limit for every move, on top of the clock
+    pub move_time: Option<Duration>,
+    // the score of every move is off by up to this much
+    pub noise: i32,
+    // how often it plays some other move than the best one
+    pub mistakes: f64,
+    // play from the opening book while it has moves
+    pub book: bool,
+    // the difficulty level the settings are from
+    pub level: Option<&'static str>,
+}
+
+impl Default for EngineSettings {
+    fn default() -> EngineSettings {
+        EngineSettings {
+            depth: 6,
+            params: None,
+            move_time: None,
+            noise: 0,
+            mistakes: 0.0,
+            book: false,
+            level: None,
+        }
+    }
+}
+
+pub const LEVELS: [&str; 5] = ["beginner", "easy", "medium", "hard", "expert"];
+
+impl EngineSettings {
+    pub fn level(name: &str) -> Option<EngineSettings> {
+        let name = *LEVELS.iter().find(|&&level| level == name)?;
+        let (depth, noise, mistakes) = match name {
+            "beginner" => (1, 40, 0.5),
+            "easy" => (2, 20, 0.25),
+            "medium" => (3, 8, 0.1),
+            "hard" => (5, 0, 0.0),
+            _ => (8, 0, 0.0),
+        };
+        Some(EngineSettings {
+            depth,
+            move_time: if name == "expert" { Some(Duration::from_secs(5)) } else { None },
+            noise,
+            mistakes,
+            book: name != "beginner",
+            level: Some(name),
+            ..EngineSettings::default()
+        })
+    }
+
+    // Whether it always plays the move its search likes best
+    pub fn plays_best(&self) -> bool {
+        self.noise == 0 && self.mistakes == 0.0
+    }
+}
+
+// A move that is this much worse than another is e times less likely to be picked as a mistake
+const MISTAKE_SPREAD: f64 = 30.0;
+
+// The engine's move and its score, which is None for a book move. The weaker settings
+// score every move and pick one at random; they search so shallow that they need no time limit.
+// With a progress in `stat`, the search for the best move reports every depth it finishes.
+pub fn engine_move<R: Rng>(
+    position: &Position,
+    settings: &EngineSettings,
+    budget: Option<Duration>,
+    params: &EvalParams,
+    book: &Book,
+    rng: &mut R,
+    stat: &mut Stat) -> Option<(Pos2D, Option<i32>)> {
+    let (board, color) = (&position.board, position.to_move);
+    if settings.book {
+        if let Some(mv) = book.pick(position, rng) {
+            return Some((mv, None));
+        }
+    }
+    if settings.plays_best() {
+        let budget = match (budget, settings.move_time) {
+            (Some(budget), Some(limit)) => Some(budget.min(limit)),
+            (budget, limit) => budget.or(limit),
+        };
+        let (mv, score) = match budget {
+            _ if stat.progress.is_some() => principal_variation_timed(board, color, settings.depth, budget, params, stat),
+            Some(budget) => negamax_timed(board, color, settings.depth, budget, params, stat),
+            None => negamax(board, color, settings.depth, params, stat),
+        }?;
+        return Some((mv, Some(score)));
+    }
+
+    let mut scored: Vec<(Pos2D, i32)> = board
+        .get_available_moves_for(color)
+        .map(|mv| {
+            let mut child = Board::new_from(board);
+            child.place(mv, color);
+            stat.nodes_viewed += 1;
+            let (score, _) = negamax_worker(&child, color.opposite(), settings.depth * 2 - 1, params, i32::MIN + 1, i32::MAX - 1, stat);
+            (mv, -score + rng.gen_range(-settings.noise, settings.noise + 1))
+        })
+        .collect();
+    scored.sort_by_key(|&(_, score)| -score);
+    let &(best, best_score) = scored.first()?;
+    if scored.len() == 1 || !rng.gen_bool(settings.mistakes) {
+        return Some((best, Some(best_score)));
+    }
+    // A mistake: one of the other moves, the better ones more likely
+    let others = &scored[1..];
+    let weights: Vec<f64> = others.iter().map(|&(_, score)| ((score - best_score) as f64 / MISTAKE_SPREAD).exp()).collect();
+    let mut x = rng.gen::<f64>() * weights.iter().sum::<f64>();
+    for (&(mv, score), weight) in others.iter().zip(&weights) {
+        if x < *weight {
+            return Some((mv, Some(score)));
+        }
+        x -= weight;
+    }
+    others.last().map(|&(mv, score)| (mv, Some(score)))
+}
+
+// Who makes the moves for one side
+#[derive(Clone, Debug, PartialEq)]
+pub enum Player {
+    Human,
+    Engine(EngineSettings),
+}
+
+impl FromStr for Player {
+    type Err = String;
+
+    // "human", "engine", "engine:<depth>" or a difficulty level
+    fn from_str(s: &str) -> Result<Player, String> {
+        match s.trim().to_lowercase().as_str() {
+            "human" => Ok(Player::Human),
+            "engine" => Ok(Player::Engine(EngineSettings::default())),
+            other => match (other.strip_prefix("engine:").map(|n| n.parse()), EngineSettings::level(other)) {
+                (Some(Ok(depth)), _) if depth > 0 => Ok(Player::Engine(EngineSettings { depth, ..EngineSettings::default() })),
+                (_, Some(settings)) => Ok(Player::Engine(settings)),
+                _ => Err(format!("unknown player '{}'", s)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Player::Human => write!(f, "human"),
+            Player::Engine(settings) => match settings.level {
+                Some(level) => write!(f, "engine, {} (depth {})", level, settings.depth),
+                None => write!(f, "engine (depth {})", settings.depth),
+            },
+        }
+    }
+}
+
+// The players of both sides
+#[derive(Clone, Debug, PartialEq)]
+pub struct Players {
+    pub black: Player,
+    pub white: Player,
+}
+
+impl Default for Players {
+    fn default() -> Players {
+        Players {
+            black: Player::Human,
+            white: Player::Engine(EngineSettings::default()),
+        }
+    }
+}
+
+impl Players {
+    pub fn of(&self, color: Color) -> &Player {
+        match color {
+            Color::Black => &self.black,
+            Color::White => &self.white,
+            Color::Empty => panic!("nobody plays empty"),
+        }
+    }
+
+    pub fn of_mut(&mut self, color: Color) -> &mut Player {
+        match color {
+            Color::Black => &mut self.black,
+            Color::White => &mut self.white,
+            Color::Empty => panic!("nobody plays empty"),
+        }
+    }
+
+    // Black's player takes over White and the other way round
+    pub fn swap(&mut self) {
+        std::mem::swap(&mut self.black, &mut self.white);
+    }
+
+    pub fn engines_only(&self) -> bool {
+        matches!((&self.black, &self.white), (Player::Engine(_), Player::Engine(_)))
+    }
+}
+
+// What happens after an engine move when no human is playing
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pause {
+    None,
+    Millis(u64),
+    // wait for Enter
+    Step,
+}
+
+impl FromStr for Pause {
+    type Err = String;
+
+    // a number of milliseconds, or "enter"
+    fn from_str(s: &str) -> Result<Pause, String> {
+        match s.trim().to_lowercase().as_str() {
+            "enter" => Ok(Pause::Step),
+            "0" => Ok(Pause::None),
+            other => other
+                .parse()
+                .map(Pause::Millis)
+                .map_err(|_| format!("unknown pause '{}'", s)),
+        }
+    }
+}
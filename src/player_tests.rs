@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod test {
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::board::*;
+    use crate::book::Book;
+    use crate::color::*;
+    use crate::params::EvalParams;
+    use crate::player::*;
+    use crate::position::Position;
+    use crate::stat::Stat;
+
+    #[test]
+    fn test_parse_players() {
+        assert_eq!("human".parse::<Player>(), Ok(Player::Human));
+        assert_eq!("Engine".parse::<Player>(), Ok(Player::Engine(EngineSettings::default())));
+        assert_eq!(
+            "engine:3".parse::<Player>(),
+            Ok(Player::Engine(EngineSettings { depth: 3, ..EngineSettings::default() }))
+        );
+        assert!("engine:0".parse::<Player>().is_err());
+        assert!("robot".parse::<Player>().is_err());
+
+        assert_eq!("enter".parse::<Pause>(), Ok(Pause::Step));
+        assert_eq!("0".parse::<Pause>(), Ok(Pause::None));
+        assert_eq!("500".parse::<Pause>(), Ok(Pause::Millis(500)));
+        assert!("soon".parse::<Pause>().is_err());
+    }
+
+    #[test]
+    fn test_swap_sides() {
+        let mut players = Players::default();
+        assert_eq!(players.of(Color::Black), &Player::Human);
+        assert!(!players.engines_only());
+        players.swap();
+        assert_eq!(players.of(Color::White), &Player::Human);
+        assert_eq!(players.of(Color::Black).to_string(), "engine (depth 6)");
+
+        *players.of_mut(Color::White) = Player::Engine(EngineSettings { depth: 2, ..EngineSettings::default() });
+        assert!(players.engines_only());
+    }
+
+    #[test]
+    fn test_parse_levels() {
+        for name in LEVELS.iter() {
+            match name.parse::<Player>() {
+                Ok(Player::Engine(settings)) => assert_eq!(settings.level, Some(*name)),
+                other => panic!("{} parsed as {:?}", name, other),
+            }
+        }
+        let beginner = EngineSettings::level("beginner").unwrap();
+        assert!(!beginner.plays_best() && !beginner.book);
+        assert!(EngineSettings::level("hard").unwrap().plays_best());
+        assert_eq!("Medium".parse::<Player>().unwrap().to_string(), "engine, medium (depth 3)");
+        assert_eq!(EngineSettings::level("grandmaster"), None);
+    }
+
+    // The moves of a game between two levels, with all chance coming from the seed
+    fn game(seed: u64, black: &EngineSettings, white: &EngineSettings) -> Vec<(Pos2D, Option<i32>)> {
+        let (params, book) = (EvalParams::default(), Book::builtin());
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut board = Board::new_start();
+        let mut color = Color::Black;
+        let mut moves = vec![];
+        while moves.len() < 16 && board.has_any_moves(color) {
+            let settings = if color == Color::Black { black } else { white };
+            let (mv, score) = engine_move(&Position::new(&board, color), settings, None, &params, &book, &mut rng, &mut Stat::new()).unwrap();
+            board.place(mv, color);
+            moves.push((mv, score));
+            color = color.opposite();
+        }
+        moves
+    }
+
+    #[test]
+    fn test_seed_replays_the_game() {
+        let beginner = EngineSettings::level("beginner").unwrap();
+        let easy = EngineSettings::level("easy").unwrap();
+        let first = game(7, &beginner, &easy);
+        assert_eq!(first, game(7, &beginner, &easy));
+        assert!((1..20).any(|seed| game(seed, &beginner, &easy) != first));
+        // easy answers the first move from the book
+        assert_eq!(first[1].1, None);
+        assert!(first.iter().step_by(2).all(|&(_, score)| score.is_some()));
+    }
+}
@@ -0,0 +1,135 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::analysis::*;
+use crate::board::*;
+use crate::color::Color;
+use crate::params::EvalParams;
+use crate::position::Position;
+use crate::stat::Stat;
+use crate::table::Table;
+
+// What the engine thinks about while the human is to move
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ponder {
+    Off,
+    // its answer to the reply it expects
+    Guess,
+    // its answers to every reply, one depth at a time
+    All,
+}
+
+impl FromStr for Ponder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Ponder, String> {
+        match s.trim().to_lowercase().as_str() {
+            "off" => Ok(Ponder::Off),
+            "guess" => Ok(Ponder::Guess),
+            "all" => Ok(Ponder::All),
+            other => Err(format!("unknown ponder mode '{}'", other)),
+        }
+    }
+}
+
+// The engine's move in a position it pondered on, and how deep it searched
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pondered {
+    pub mv: Pos2D,
+    pub score: i32,
+    pub depth: i32,
+}
+
+// What pondering leaves to the engine's own search: the finished ponder searches, by the
+// position they were for, and the table of every position searched, also by a search
+// that was stopped part way, so that the engine's search goes faster even if it has to
+// search again
+#[derive(Clone, Default)]
+pub struct PonderResults {
+    pub finished: Arc<Mutex<HashMap<String, Pondered>>>,
+    pub table: Arc<Table>,
+}
+
+impl PonderResults {
+    // Forgets everything, e.g. when the engine or its parameters change
+    pub fn clear(&self) {
+        self.finished.lock().unwrap().clear();
+        self.table.clear();
+    }
+}
+
+// The engine's move in `position`, if a ponder search at least as deep as `depth` found it
+pub fn pondered_move(results: &PonderResults, position: &Position, depth: i32) -> Option<Pondered> {
+    let finished = results.finished.lock().unwrap();
+    finished.get(&position.to_string()).copied().filter(|pondered| pondered.depth >= depth)
+}
+
+// A search on the human's time, which stops when dropped
+pub struct Ponderer {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Ponderer {
+    // Starts thinking, while `human` is to move on `board`, about the engine's answers
+    // to the human's replies, up to `depth`. Finished results from earlier turns are
+    // forgotten; the table is kept, as its positions may come up again.
+    pub fn start(board: &Board, human: Color, mode: Ponder, depth: i32, params: &EvalParams, results: &PonderResults) -> Ponderer {
+        results.finished.lock().unwrap().clear();
+        let stop = Arc::new(AtomicBool::new(false));
+        let board = Board::new_from(board);
+        let params = params.clone();
+        let (thread_stop, results) = (stop.clone(), results.clone());
+        let handle = thread::spawn(move || ponder(&board, human, mode, depth, &params, &thread_stop, &results));
+        Ponderer { stop, handle: Some(handle) }
+    }
+}
+
+impl Drop for Ponderer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// One depth at a time over all the replies; a finished result is used as it is if it is as
+// deep as the engine's own search, and the table in any case
+fn ponder(board: &Board, human: Color, mode: Ponder, depth: i32, params: &EvalParams, stop: &Arc<AtomicBool>, results: &PonderResults) {
+    let engine = human.opposite();
+    let new_stat = || {
+        let mut stat = Stat::new();
+        stat.stop = Some(stop.clone());
+        stat.table = Some(results.table.clone());
+        stat
+    };
+    let replies: Vec<Pos2D> = match mode {
+        Ponder::Off => return,
+        Ponder::Guess => match negamax(board, human, 1, params, &mut new_stat()) {
+            Some((reply, _)) => vec![reply],
+            None => return,
+        },
+        Ponder::All => board.get_available_moves_for(human).collect(),
+    };
+    for d in 1..=depth {
+        for &reply in &replies {
+            let mut child = Board::new_from(board);
+            child.place(reply, human);
+            let mut stat = new_stat();
+            let result = negamax(&child, engine, d, params, &mut stat);
+            if stat.aborted {
+                return;
+            }
+            if let Some((mv, score)) = result {
+                let key = Position::new(&child, engine).to_string();
+                results.finished.lock().unwrap().insert(key, Pondered { mv, score, depth: d });
+            }
+        }
+    }
+}
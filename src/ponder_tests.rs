@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod test {
+
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use crate::analysis::*;
+    use crate::board::*;
+    use crate::color::*;
+    use crate::params::EvalParams;
+    use crate::ponder::*;
+    use crate::position::*;
+    use crate::stat::Stat;
+
+    #[test]
+    fn test_stop_flag_aborts_search() {
+        let stop = Arc::new(AtomicBool::new(true));
+        let mut stat = Stat::new();
+        stat.stop = Some(stop);
+        negamax(&Board::new_start(), Color::Black, 6, &EvalParams::default(), &mut stat);
+        assert!(stat.aborted);
+        assert!(stat.nodes_viewed < 256);
+
+        let mut stat = Stat::new();
+        stat.stop = Some(Arc::new(AtomicBool::new(false)));
+        negamax(&Board::new_start(), Color::Black, 2, &EvalParams::default(), &mut stat);
+        assert!(!stat.aborted);
+    }
+
+    #[test]
+    fn test_ponder_every_reply() {
+        let params = EvalParams::default();
+        let results = PonderResults::default();
+        let board = Board::new_start();
+        let ponderer = Ponderer::start(&board, Color::Black, Ponder::All, 2, &params, &results);
+
+        // all four replies of Black get an answer at depth 2 soon enough
+        let waited = Instant::now();
+        while results.finished.lock().unwrap().values().filter(|p| p.depth == 2).count() < 4 {
+            assert!(waited.elapsed() < Duration::from_secs(20));
+            thread::sleep(Duration::from_millis(10));
+        }
+        drop(ponderer);
+
+        for reply in board.get_available_moves_for(Color::Black) {
+            let mut child = Board::new_from(&board);
+            child.place(reply, Color::Black);
+            let position = Position::new(&child, Color::White);
+            let pondered = pondered_move(&results, &position, 2).unwrap();
+            let (mv, score) = negamax(&child, Color::White, 2, &params, &mut Stat::new()).unwrap();
+            assert_eq!((pondered.mv, pondered.score), (mv, score));
+            assert_eq!(pondered_move(&results, &position, 3), None);
+        }
+    }
+
+    #[test]
+    fn test_dropping_stops_the_search() {
+        let results = PonderResults::default();
+        let ponderer = Ponderer::start(&Board::new_start(), Color::Black, Ponder::Guess, 20, &EvalParams::default(), &results);
+        thread::sleep(Duration::from_millis(50));
+        let stopping = Instant::now();
+        drop(ponderer);
+        assert!(stopping.elapsed() < Duration::from_secs(2));
+        assert!(results.finished.lock().unwrap().len() <= 1);
+    }
+
+    #[test]
+    fn test_stopped_ponder_leaves_the_table() {
+        let params = EvalParams::default();
+        let results = PonderResults::default();
+        let board = Board::new_start();
+        let ponderer = Ponderer::start(&board, Color::Black, Ponder::Guess, 20, &params, &results);
+        let waited = Instant::now();
+        while results.table.is_empty() {
+            assert!(waited.elapsed() < Duration::from_secs(20));
+            thread::sleep(Duration::from_millis(10));
+        }
+        // most likely in the middle of a deeper search by now
+        thread::sleep(Duration::from_millis(200));
+        drop(ponderer);
+        assert!(!results.table.is_empty());
+
+        // the engine's search after the expected reply looks at fewer positions with the
+        // table; what was searched deeper may settle it at once
+        let (reply, _) = negamax(&board, Color::Black, 1, &params, &mut Stat::new()).unwrap();
+        let mut child = Board::new_from(&board);
+        child.place(reply, Color::Black);
+        let mut alone = Stat::new();
+        negamax(&child, Color::White, 3, &params, &mut alone).unwrap();
+        let mut stat = Stat::new();
+        stat.table = Some(results.table.clone());
+        let (mv, _) = negamax(&child, Color::White, 3, &params, &mut stat).unwrap();
+        assert!(child.get_available_moves_for(Color::White).any(|p| p == mv));
+        assert!(stat.nodes_viewed < alone.nodes_viewed);
+
+        results.clear();
+        assert!(results.table.is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+#![allow(dead_code)]
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::board::*;
+use crate::color::Color;
+
+// A board together with the side to move, written as the 64 cells followed by
+// X or O, as in "---...---XO------OX---...--- X". Other board sizes have as many
+// characters as cells.
+pub struct Position {
+    pub board: Board,
+    pub to_move: Color,
+}
+
+impl Position {
+    pub fn new(board: &Board, to_move: Color) -> Position {
+        Position {
+            board: Board::new_from(board),
+            to_move,
+        }
+    }
+
+    pub fn start() -> Position {
+        Position::new(&Board::new_start(), Color::Black)
+    }
+
+    pub fn start_sized(size: usize) -> Position {
+        Position::new(&Board::new_start_sized(size), Color::Black)
+    }
+
+    // Takes a position either literally or from the first non-empty line of a file
+    pub fn from_arg(arg: &str) -> Result<Position, String> {
+        if Path::new(arg).is_file() {
+            let text = fs::read_to_string(arg).map_err(|e| format!("cannot read {}: {}", arg, e))?;
+            let line = text.lines().map(|l| l.trim()).find(|l| !l.is_empty()).unwrap_or("");
+            line.parse()
+        } else {
+            arg.parse()
+        }
+    }
+}
+
+impl Clone for Position {
+    fn clone(&self) -> Position {
+        Position::new(&self.board, self.to_move)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side = if self.to_move == Color::Black { 'X' } else { 'O' };
+        write!(f, "{} {}", self.board, side)
+    }
+}
+
+impl FromStr for Position {
+    type Err = String;
+
+    // The side to move may follow the cells with or without a space in between
+    fn from_str(s: &str) -> Result<Position, String> {
+        let s = s.trim().trim_end_matches(';').trim_end();
+        let count = s.chars().count();
+        if [16, 36, 64, 100].contains(&count) {
+            return Err(String::from("the side to move is missing"));
+        }
+        let side = s.chars().last().unwrap_or(' ');
+        let to_move = match side {
+            'X' | 'x' | '*' | 'B' | 'b' => Color::Black,
+            'O' | 'o' | 'W' | 'w' => Color::White,
+            other => return Err(format!("bad side to move '{}'", other)),
+        };
+        let cells: String = s.chars().take(count.saturating_sub(1)).collect();
+        Ok(Position {
+            board: cells.parse()?,
+            to_move,
+        })
+    }
+}
@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod test {
+
+    use crate::board::*;
+    use crate::color::*;
+    use crate::position::*;
+
+    const START: &str = "---------------------------XO------OX---------------------------";
+
+    #[test]
+    fn test_board_round_trip() {
+        let b = Board::new_start();
+        assert_eq!(b.to_string(), START);
+        assert_eq!(START.parse::<Board>().unwrap().to_string(), START);
+        assert!("XO-".parse::<Board>().is_err());
+        assert!(START.replace('X', "B").parse::<Board>().is_err());
+    }
+
+    #[test]
+    fn test_position_round_trip() {
+        let p = Position::start();
+        assert_eq!(p.to_string(), format!("{} X", START));
+
+        let parsed: Position = format!("{}O", START).parse().unwrap();
+        assert_eq!(parsed.to_move, Color::White);
+        assert_eq!(parsed.board.to_string(), START);
+        let parsed: Position = format!("  {} X;", START).parse().unwrap();
+        assert_eq!(parsed.to_move, Color::Black);
+
+        assert!(START.parse::<Position>().is_err());
+        assert!(format!("{} Z", START).parse::<Position>().is_err());
+        assert!("XO- X".parse::<Position>().is_err());
+    }
+}
@@ -0,0 +1,298 @@
+#![allow(dead_code)]
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+
+use crate::analysis::*;
+use crate::board::*;
+use crate::color::Color;
+use crate::openings::Openings;
+use crate::params::EvalParams;
+use crate::position::Position;
+use crate::review::read_transcripts;
+use crate::selfplay::*;
+use crate::solve::*;
+use crate::stat::Stat;
+use crate::symmetry::PositionMap;
+use crate::transcript::*;
+
+// A position with a single right answer: the only move that wins, or one that ends at
+// least some discs ahead of every other move. Scores are exact final scores for the
+// side to move. Puzzles are always for normal reversi.
+pub struct Puzzle {
+    pub position: Position,
+    pub solution: Pos2D,
+    pub score: i32,
+    // the best the other moves can do
+    pub next_best: i32,
+    // from 1 for easy to 5 for hard
+    pub difficulty: u32,
+}
+
+impl Puzzle {
+    pub fn only_win(&self) -> bool {
+        self.score > 0 && self.next_best <= 0
+    }
+
+    pub fn prompt(&self) -> String {
+        if self.only_win() {
+            format!("{:?} to move: find the only winning move", self.position.to_move)
+        } else {
+            format!(
+                "{:?} to move: find the move that ends {} discs better than any other",
+                self.position.to_move,
+                self.score - self.next_best
+            )
+        }
+    }
+}
+
+// One line per puzzle: the position, the solution, both scores and the difficulty, as in
+// "---...---XO------OX---...--- X c4 +6 -2 3"
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {:+} {:+} {}", self.position, self.solution, self.score, self.next_best, self.difficulty)
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Puzzle, String> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        if fields.len() != 6 {
+            return Err(format!("expected 6 fields in '{}'", s));
+        }
+        let number = |field: &str| field.parse::<i32>().map_err(|_| format!("bad number '{}'", field));
+        Ok(Puzzle {
+            position: format!("{} {}", fields[0], fields[1]).parse()?,
+            solution: Pos2D::parse(fields[2]).ok_or_else(|| format!("bad move '{}'", fields[2]))?,
+            score: number(fields[3])?,
+            next_best: number(fields[4])?,
+            difficulty: number(fields[5])?.clamp(1, 5) as u32,
+        })
+    }
+}
+
+pub fn load_puzzles(path: &str) -> Result<Vec<Puzzle>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+    text.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.parse())
+        .collect()
+}
+
+pub fn save_puzzles(path: &str, puzzles: &[Puzzle]) -> Result<(), String> {
+    let text: String = puzzles.iter().map(|p| format!("{}\n", p)).collect();
+    fs::write(path, text).map_err(|e| format!("cannot write {}: {}", path, e))
+}
+
+// The puzzle in a position, if it has one. Every move is solved exactly, so this is only
+// practical close to the end of the game.
+pub fn find_puzzle(board: &Board, color: Color, min_margin: i32, params: &EvalParams, stat: &mut Stat) -> Option<Puzzle> {
+    let mut scores: Vec<(Pos2D, i32)> = board
+        .get_available_moves_for(color)
+        .map(|mv| {
+            let mut child = Board::new_from(board);
+            child.place(mv, color);
+            let (score, _) = solve_exact(&child, color.opposite(), stat);
+            (mv, -score)
+        })
+        .collect();
+    if scores.len() < 2 {
+        return None;
+    }
+    scores.sort_by_key(|&(_, score)| -score);
+    let (solution, score) = scores[0];
+    let next_best = scores[1].1;
+    let only_win = score > 0 && next_best <= 0;
+    if score == next_best || (!only_win && score - next_best < min_margin) {
+        return None;
+    }
+    Some(Puzzle {
+        position: Position::new(board, color),
+        solution,
+        score,
+        next_best,
+        difficulty: rate(board, color, solution, scores.len(), params, stat),
+    })
+}
+
+// One point to start with, one each if a search one and three moves deep misses the
+// solution, one for having many moves to pick from and one for a long way to the end
+fn rate(board: &Board, color: Color, solution: Pos2D, moves: usize, params: &EvalParams, stat: &mut Stat) -> u32 {
+    let misses = |depth, stat: &mut Stat| negamax(board, color, depth, params, stat).map(|(mv, _)| mv) != Some(solution);
+    let mut difficulty = 1;
+    if misses(1, stat) {
+        difficulty += 1;
+    }
+    if misses(3, stat) {
+        difficulty += 1;
+    }
+    if moves >= 6 {
+        difficulty += 1;
+    }
+    if board.num_empty() >= 10 {
+        difficulty += 1;
+    }
+    difficulty
+}
+
+// Puzzles from whole games, each position only once up to symmetry
+pub struct PuzzleFinder {
+    // positions with more empty cells are too slow to solve every move of
+    pub max_empties: usize,
+    // how much better than any other the solution must be, unless it is the only win
+    pub min_margin: i32,
+    pub puzzles: Vec<Puzzle>,
+    seen: PositionMap<()>,
+}
+
+impl PuzzleFinder {
+    pub fn new(max_empties: usize, min_margin: i32) -> PuzzleFinder {
+        PuzzleFinder { max_empties, min_margin, puzzles: vec![], seen: PositionMap::new() }
+    }
+
+    pub fn add_game(&mut self, transcript: &Transcript, params: &EvalParams, stat: &mut Stat) {
+        if transcript.variant != Variant::Normal {
+            return;
+        }
+        let mut board = transcript.start_board();
+        for &(mover, p) in &transcript.moves {
+            if !board.can_place(p, mover) {
+                return;
+            }
+            if board.num_empty() <= self.max_empties && self.seen.insert_new(&Position::new(&board, mover), ()) {
+                if let Some(puzzle) = find_puzzle(&board, mover, self.min_margin, params, stat) {
+                    self.puzzles.push(puzzle);
+                }
+            }
+            board.place(p, mover);
+        }
+    }
+}
+
+pub struct PuzzleOptions {
+    // self-play games to look for puzzles in
+    pub games: usize,
+    // file with one transcript per line, looked at in addition to the self-play games
+    pub transcripts: Option<String>,
+    pub empties: usize,
+    pub margin: i32,
+    // search depth for self-play
+    pub depth: i32,
+    pub random_plies: usize,
+    pub seed: Option<u64>,
+    pub out: String,
+}
+
+impl Default for PuzzleOptions {
+    fn default() -> PuzzleOptions {
+        PuzzleOptions {
+            games: 20,
+            transcripts: None,
+            empties: 12,
+            margin: 10,
+            depth: 2,
+            random_plies: 8,
+            seed: None,
+            out: String::from("puzzles.txt"),
+        }
+    }
+}
+
+pub fn run_puzzles(options: &PuzzleOptions, params: &EvalParams) -> Result<(), String> {
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let mut stat = Stat::new();
+    let mut finder = PuzzleFinder::new(options.empties, options.margin);
+
+    if let Some(path) = &options.transcripts {
+        for transcript in read_transcripts(path)? {
+            finder.add_game(&transcript, params, &mut stat);
+        }
+        println!("Found {} puzzles in {}", finder.puzzles.len(), path);
+    }
+
+    let openings = Openings::Random(options.random_plies);
+    for game in 0..options.games {
+        let (transcript, _) = play_out(&openings.pick(&mut rng), params, params, options.depth)?;
+        finder.add_game(&transcript, params, &mut stat);
+        if (game + 1) % 10 == 0 {
+            println!("Looked at {} self-play games, {} puzzles so far", game + 1, finder.puzzles.len());
+        }
+    }
+    if finder.puzzles.is_empty() {
+        return Err(String::from("no puzzles found"));
+    }
+    let mut puzzles = finder.puzzles;
+    puzzles.sort_by_key(|p| p.difficulty);
+    for difficulty in 1..=5 {
+        let count = puzzles.iter().filter(|p| p.difficulty == difficulty).count();
+        println!("Difficulty {}: {} puzzles", difficulty, count);
+    }
+    save_puzzles(&options.out, &puzzles)?;
+    println!("Wrote {} puzzles to {}", puzzles.len(), options.out);
+    Ok(())
+}
+
+// Goes through puzzles, the easier ones first, and keeps the score
+pub struct Trainer {
+    puzzles: Vec<Puzzle>,
+    next: usize,
+    pub solved: usize,
+    pub attempted: usize,
+    // the difficulty of every solved puzzle added up
+    pub points: u32,
+}
+
+impl Trainer {
+    pub fn new(mut puzzles: Vec<Puzzle>) -> Trainer {
+        puzzles.sort_by_key(|p| p.difficulty);
+        Trainer { puzzles, next: 0, solved: 0, attempted: 0, points: 0 }
+    }
+
+    pub fn current(&self) -> Option<&Puzzle> {
+        self.puzzles.get(self.next)
+    }
+
+    // Checks the answer to the current puzzle and moves on to the next one
+    pub fn answer(&mut self, mv: Pos2D) -> bool {
+        let (solution, difficulty) = match self.current() {
+            Some(puzzle) => (puzzle.solution, puzzle.difficulty),
+            None => return false,
+        };
+        let right = mv == solution;
+        if right {
+            self.solved += 1;
+            self.points += difficulty;
+        }
+        self.attempted += 1;
+        self.next += 1;
+        right
+    }
+
+    // Gives up on the current puzzle, which counts as not solved
+    pub fn skip(&mut self) {
+        if self.current().is_some() {
+            self.attempted += 1;
+            self.next += 1;
+        }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.puzzles.len() - self.next
+    }
+}
+
+impl fmt::Display for Trainer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Solved {} of {}, {} points", self.solved, self.attempted, self.points)
+    }
+}
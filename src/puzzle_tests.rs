@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod test {
+
+    use crate::board::*;
+    use crate::options::Options;
+    use crate::params::*;
+    use crate::puzzle::*;
+    use crate::selfplay::*;
+    use crate::stat::Stat;
+    use crate::transcript::*;
+
+    // Found in self-play: a0 is the only move that wins
+    const ONLY_WIN: &str = "-OOXXX-X--OXXXX-XXOOXXOOXXXOOXXOXXOOOOOOXXOOXOOO--OOOOOO-OOOOOOO X a0 +2 -4 1";
+    const BETTER: &str = "OOOOOOO-XOOOOOOOXXOOOXOXXXXOXOXXXOOXXXXXXOXOXXOXXXXXXX-XXXXXXX-- X h0 +12 +2 1";
+
+    #[test]
+    fn test_puzzle_lines_round_trip() {
+        let puzzle: Puzzle = ONLY_WIN.parse().unwrap();
+        assert_eq!(puzzle.solution, Pos2D::new(0, 0));
+        assert!(puzzle.only_win());
+        assert_eq!(puzzle.to_string(), ONLY_WIN);
+        assert!("not a puzzle".parse::<Puzzle>().is_err());
+    }
+
+    #[test]
+    fn test_finds_puzzles_by_exact_search() {
+        for line in &[ONLY_WIN, BETTER] {
+            let expected: Puzzle = line.parse().unwrap();
+            let position = &expected.position;
+            let found = find_puzzle(&position.board, position.to_move, 10, &EvalParams::default(), &mut Stat::new()).unwrap();
+            assert_eq!((found.solution, found.score, found.next_best), (expected.solution, expected.score, expected.next_best));
+        }
+        // h0 is only 10 better, which is not enough when asking for more
+        let better: Puzzle = BETTER.parse().unwrap();
+        assert!(find_puzzle(&better.position.board, better.position.to_move, 11, &EvalParams::default(), &mut Stat::new()).is_none());
+    }
+
+    #[test]
+    fn test_finder_skips_repeated_positions() {
+        let params = EvalParams::default();
+        let (game, _) = play_out(&Transcript::new(), &params, &params, 1).unwrap();
+        let mut finder = PuzzleFinder::new(8, 1);
+        finder.add_game(&game, &params, &mut Stat::new());
+        let found = finder.puzzles.len();
+        assert!(found > 0);
+        assert!(finder.puzzles.iter().all(|p| p.position.board.num_empty() <= 8));
+        finder.add_game(&game, &params, &mut Stat::new());
+        assert_eq!(finder.puzzles.len(), found);
+    }
+
+    #[test]
+    fn test_trainer_keeps_score() {
+        let puzzles = vec![BETTER.parse().unwrap(), ONLY_WIN.parse().unwrap(), ONLY_WIN.parse().unwrap()];
+        let mut trainer = Trainer::new(puzzles);
+        assert!(trainer.answer(Pos2D::new(7, 0)));
+        assert!(!trainer.answer(Pos2D::new(6, 0)));
+        trainer.skip();
+        assert!(trainer.current().is_none());
+        assert_eq!((trainer.solved, trainer.attempted, trainer.points), (1, 3, 1));
+        assert_eq!(trainer.to_string(), "Solved 1 of 3, 1 points");
+    }
+
+    #[test]
+    fn test_depth_must_be_positive() {
+        let args: Vec<String> = ["puzzles", "--depth", "0"].iter().map(|s| s.to_string()).collect();
+        assert!(Options::from_args(&args).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+#![allow(dead_code)]
+
+use std::str::FromStr;
+
+use crate::board::*;
+use crate::color::Color;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RenderStyle {
+    // the box-drawn grid
+    Grid,
+    // the grid with colored discs on a green board, for terminals that understand ANSI colors
+    Ansi,
+    // plain characters only, three per cell, for logs and CI
+    Ascii,
+}
+
+impl FromStr for RenderStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<RenderStyle, String> {
+        match s.trim().to_lowercase().as_str() {
+            "grid" => Ok(RenderStyle::Grid),
+            "ansi" | "color" => Ok(RenderStyle::Ansi),
+            "ascii" => Ok(RenderStyle::Ascii),
+            other => Err(format!("unknown board style '{}'", other)),
+        }
+    }
+}
+
+// What to point out on the board besides the discs
+#[derive(Default)]
+pub struct Highlights {
+    // mark the cells this side can move to
+    pub to_move: Option<Color>,
+    pub last_move: Option<Pos2D>,
+    pub flipped: Vec<Pos2D>,
+}
+
+impl Highlights {
+    // The last move of `mover`, which changed the board from `before` to `after`,
+    // with the moves of the other side marked
+    pub fn after_move(before: &Board, after: &Board, mv: Pos2D, mover: Color) -> Highlights {
+        Highlights {
+            to_move: Some(mover.opposite()),
+            last_move: Some(mv),
+            flipped: flipped_by(before, after, mv),
+        }
+    }
+}
+
+// The discs a move turned over
+pub fn flipped_by(before: &Board, after: &Board, mv: Pos2D) -> Vec<Pos2D> {
+    after
+        .cells()
+        .filter(|&p| p != mv && before.get_at(p) != after.get_at(p))
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct Renderer {
+    pub style: RenderStyle,
+    // mark legal moves, the last move and the discs it turned over
+    pub markers: bool,
+    // label rows 1 to 8 as in standard notation, rather than 0 to 7 as transcripts and the
+    // prompt do, so only for boards that are read and not played on
+    pub standard_labels: bool,
+    // turn the board around, as seen from White's side of the table
+    pub white_view: bool,
+}
+
+impl Default for Renderer {
+    fn default() -> Renderer {
+        Renderer {
+            style: RenderStyle::Grid,
+            markers: true,
+            standard_labels: false,
+            white_view: false,
+        }
+    }
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+impl Renderer {
+    pub fn print(&self, board: &Board, highlights: &Highlights) {
+        print!("{}", self.render(board, highlights));
+    }
+
+    // A move named the way the rows are labelled
+    pub fn move_name(&self, p: Pos2D) -> String {
+        if self.standard_labels {
+            p.to_standard()
+        } else {
+            p.to_string()
+        }
+    }
+
+    pub fn render(&self, board: &Board, highlights: &Highlights) -> String {
+        let size = board.size();
+        // screen order of rows and columns
+        let order: Vec<usize> = if self.white_view { (0..size).rev().collect() } else { (0..size).collect() };
+        let column_label = |i: usize| {
+            let c = (b'a' + i as u8) as char;
+            if self.style == RenderStyle::Ascii { c } else { c.to_ascii_uppercase() }
+        };
+        let row_label = |j: usize| if self.standard_labels { j + 1 } else { j };
+
+        let mut out = String::new();
+        match self.style {
+            RenderStyle::Ascii => {
+                out.push_str("   ");
+                for &i in &order {
+                    out.push_str(&format!(" {} ", column_label(i)));
+                }
+                out.push('\n');
+                for &j in &order {
+                    out.push_str(&format!("{:<3}", row_label(j)));
+                    for &i in &order {
+                        out.push_str(&self.ascii_cell(board, highlights, Pos2D::new(i, j)));
+                    }
+                    out.push('\n');
+                }
+            }
+            RenderStyle::Grid | RenderStyle::Ansi => {
+                let line = |left: &str, middle: &str, right: &str| {
+                    format!("    {}{}{}\n", left, vec!["─────"; size].join(middle), right)
+                };
+                out.push_str("    ");
+                for &i in &order {
+                    out.push_str(&format!("   {}  ", column_label(i)));
+                }
+                out.push('\n');
+                out.push_str(&line("┌", "┬", "┐"));
+                for (row, &j) in order.iter().enumerate() {
+                    out.push_str(&format!("{:<3} │", row_label(j)));
+                    for &i in &order {
+                        out.push_str(&self.grid_cell(board, highlights, Pos2D::new(i, j)));
+                        out.push('│');
+                    }
+                    out.push('\n');
+                    out.push_str(&if row == size - 1 { line("└", "┴", "┘") } else { line("├", "┼", "┤") });
+                }
+            }
+        }
+        out
+    }
+
+    fn is_legal(&self, board: &Board, highlights: &Highlights, p: Pos2D) -> bool {
+        self.markers && highlights.to_move.is_some_and(|color| board.can_place(p, color))
+    }
+
+    // What is in the cell, and how it is marked: brackets for the last move,
+    // parentheses for a disc it turned over
+    fn cell_parts(&self, board: &Board, highlights: &Highlights, p: Pos2D) -> (char, char, char) {
+        let content = if board.is_blocked(p) {
+            '#'
+        } else {
+            match board.get_at(p) {
+                Color::Black => 'X',
+                Color::White => 'O',
+                Color::Empty if self.is_legal(board, highlights, p) => '*',
+                Color::Empty => '.',
+            }
+        };
+        if self.markers && highlights.last_move == Some(p) {
+            ('[', content, ']')
+        } else if self.markers && highlights.flipped.contains(&p) {
+            ('(', content, ')')
+        } else {
+            (' ', content, ' ')
+        }
+    }
+
+    fn ascii_cell(&self, board: &Board, highlights: &Highlights, p: Pos2D) -> String {
+        let (left, content, right) = self.cell_parts(board, highlights, p);
+        format!("{}{}{}", left, content, right)
+    }
+
+    fn grid_cell(&self, board: &Board, highlights: &Highlights, p: Pos2D) -> String {
+        let (left, content, right) = self.cell_parts(board, highlights, p);
+        let disc = match content {
+            'X' => "●",
+            'O' => "○",
+            '*' => "·",
+            '#' => "#",
+            _ => " ",
+        };
+        if self.style == RenderStyle::Grid {
+            return format!(" {}{}{} ", left, disc, right);
+        }
+        // On a green board: black and white discs, yellow move markers, the last move
+        // on red and turned discs on a lighter green
+        let background = if left == '[' {
+            "41"
+        } else if left == '(' {
+            "102"
+        } else {
+            "42"
+        };
+        let (foreground, disc) = match content {
+            'X' => ("30", "●"),
+            'O' => ("97", "●"),
+            '*' => ("33", "·"),
+            '#' => ("90", "#"),
+            _ => ("30", " "),
+        };
+        format!("\x1b[{};{}m  {}  {}", foreground, background, disc, ANSI_RESET)
+    }
+}
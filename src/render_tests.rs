@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod test {
+
+    use crate::board::*;
+    use crate::color::*;
+    use crate::render::*;
+
+    fn ascii() -> Renderer {
+        Renderer {
+            style: RenderStyle::Ascii,
+            ..Renderer::default()
+        }
+    }
+
+    #[test]
+    fn test_ascii_start() {
+        let board = Board::new_start_sized(4);
+        let highlights = Highlights { to_move: Some(Color::Black), ..Highlights::default() };
+        assert_eq!(
+            ascii().render(&board, &highlights),
+            "    a  b  c  d \n\
+             0   .  .  *  . \n\
+             1   .  X  O  * \n\
+             2   *  O  X  . \n\
+             3   .  *  .  . \n"
+        );
+    }
+
+    #[test]
+    fn test_ascii_last_move_and_flipped() {
+        let before = Board::new_start_sized(4);
+        let mut after = Board::new_from(&before);
+        after.place(Pos2D::new(2, 0), Color::Black);
+        let highlights = Highlights::after_move(&before, &after, Pos2D::new(2, 0), Color::Black);
+        assert_eq!(highlights.flipped, vec![Pos2D::new(2, 1)]);
+        let text = ascii().render(&after, &highlights);
+        assert_eq!(text.lines().nth(1), Some("0   .  * [X] * "));
+        assert_eq!(text.lines().nth(2), Some("1   .  X (X) . "));
+
+        let plain = Renderer { markers: false, ..ascii() };
+        assert_eq!(plain.render(&after, &highlights).lines().nth(1), Some("0   .  .  X  . "));
+    }
+
+    #[test]
+    fn test_white_view_and_standard_labels() {
+        let mut board = Board::new_sized(4);
+        board.set_at(Pos2D::new(0, 0), Color::Black);
+        board.set_blocked(Pos2D::new(3, 3), true);
+        let renderer = Renderer {
+            standard_labels: true,
+            white_view: true,
+            ..ascii()
+        };
+        assert_eq!(
+            renderer.render(&board, &Highlights::default()),
+            "    d  c  b  a \n\
+             4   #  .  .  . \n\
+             3   .  .  .  . \n\
+             2   .  .  .  . \n\
+             1   .  .  .  X \n"
+        );
+        assert_eq!(renderer.move_name(Pos2D::new(2, 3)), "c4");
+        assert_eq!(ascii().move_name(Pos2D::new(2, 3)), "c3");
+    }
+
+    #[test]
+    fn test_grid_and_ansi() {
+        let board = Board::new_start();
+        let grid = Renderer::default().render(&board, &Highlights::default());
+        let lines: Vec<&str> = grid.lines().collect();
+        assert_eq!(lines.len(), 18);
+        assert_eq!(lines[8], "3   │     │     │     │  ●  │  ○  │     │     │     │");
+        assert!(lines.iter().skip(1).all(|l| l.chars().count() == lines[1].chars().count()));
+
+        let ansi = Renderer { style: RenderStyle::Ansi, ..Renderer::default() };
+        let colored = ansi.render(&board, &Highlights::default());
+        assert!(colored.contains("\x1b[30;42m  ●  \x1b[0m"));
+        assert!(colored.contains("\x1b[97;42m  ●  \x1b[0m"));
+        assert_eq!("ascii".parse::<RenderStyle>(), Ok(RenderStyle::Ascii));
+        assert!("fancy".parse::<RenderStyle>().is_err());
+    }
+}
@@ -0,0 +1,309 @@
+#![allow(dead_code)]
+
+use serde::Serialize;
+use std::fmt;
+
+use crate::analysis::*;
+use crate::board::*;
+use crate::color::Color;
+use crate::gametree::GameTree;
+use crate::params::EvalParams;
+use crate::position::Position;
+use crate::render::*;
+use crate::stat::Stat;
+use crate::transcript::*;
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Best,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Verdict::Best => "best",
+            Verdict::Inaccuracy => "inaccuracy",
+            Verdict::Mistake => "mistake",
+            Verdict::Blunder => "blunder",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Score losses, in eval units, from which a move counts as an inaccuracy, a mistake or
+// a blunder. Anything less is as good as the best move.
+#[derive(Clone, Debug, Serialize)]
+pub struct Thresholds {
+    pub inaccuracy: i32,
+    pub mistake: i32,
+    pub blunder: i32,
+}
+
+impl Default for Thresholds {
+    fn default() -> Thresholds {
+        Thresholds {
+            inaccuracy: 10,
+            mistake: 50,
+            blunder: 100,
+        }
+    }
+}
+
+impl Thresholds {
+    pub fn classify(&self, loss: i32) -> Verdict {
+        if loss >= self.blunder {
+            Verdict::Blunder
+        } else if loss >= self.mistake {
+            Verdict::Mistake
+        } else if loss >= self.inaccuracy {
+            Verdict::Inaccuracy
+        } else {
+            Verdict::Best
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MoveReview {
+    pub ply: usize,
+    pub color: Color,
+    pub played: Pos2D,
+    pub best: Pos2D,
+    pub played_score: i32,
+    pub best_score: i32,
+    pub loss: i32,
+    pub verdict: Verdict,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PlayerSummary {
+    pub moves: usize,
+    pub best: usize,
+    pub inaccuracies: usize,
+    pub mistakes: usize,
+    pub blunders: usize,
+    pub total_loss: i32,
+    // share of moves that were as good as the best one, in percent
+    pub accuracy: f64,
+}
+
+impl PlayerSummary {
+    fn add(&mut self, review: &MoveReview) {
+        self.moves += 1;
+        self.total_loss += review.loss;
+        match review.verdict {
+            Verdict::Best => self.best += 1,
+            Verdict::Inaccuracy => self.inaccuracies += 1,
+            Verdict::Mistake => self.mistakes += 1,
+            Verdict::Blunder => self.blunders += 1,
+        }
+        self.accuracy = 100.0 * self.best as f64 / self.moves as f64;
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GameReview {
+    pub depth: i32,
+    pub thresholds: Thresholds,
+    pub moves: Vec<MoveReview>,
+    pub black: PlayerSummary,
+    pub white: PlayerSummary,
+}
+
+// Replays the game from `start` and searches every position in it to `depth`,
+// comparing the move that was played with the one the engine prefers
+pub fn review_game(
+    start: &Position,
+    transcript: &Transcript,
+    depth: i32,
+    params: &EvalParams,
+    thresholds: &Thresholds,
+    stat: &mut Stat) -> Result<GameReview, String> {
+
+    let mut review = GameReview {
+        depth,
+        thresholds: thresholds.clone(),
+        moves: vec![],
+        black: PlayerSummary::default(),
+        white: PlayerSummary::default(),
+    };
+
+    let mut board = Board::new_from(&start.board);
+    board.set_variant(transcript.variant);
+    for (index, &(color, played)) in transcript.moves.iter().enumerate() {
+        if !board.can_place(played, color) {
+            return Err(format!("move {} ({} {}) is not legal", index + 1, color, played));
+        }
+        let (best, best_score) =
+            negamax(&board, color, depth, params, stat).ok_or_else(|| format!("cannot search {} moves deep", depth))?;
+
+        let mut child = Board::new_from(&board);
+        child.place(played, color);
+        let played_score = if played == best {
+            best_score
+        } else {
+            // One ply less than the root search, so that both scores are comparable
+            let (score, _) = negamax_worker(&child, color.opposite(), depth * 2 - 1, params, i32::MIN + 1, i32::MAX - 1, stat);
+            -score
+        };
+
+        let loss = (best_score - played_score).max(0);
+        let move_review = MoveReview {
+            ply: index + 1,
+            color,
+            played,
+            best,
+            played_score,
+            best_score,
+            loss,
+            verdict: thresholds.classify(loss),
+        };
+        if color == Color::Black {
+            review.black.add(&move_review);
+        } else {
+            review.white.add(&move_review);
+        }
+        review.moves.push(move_review);
+        board = child;
+    }
+    Ok(review)
+}
+
+impl fmt::Display for GameReview {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:>4}  {:<6}{:>6}{:>6}{:>8}{:>8}{:>7}  verdict", "ply", "color", "move", "best", "score", "best", "loss")?;
+        for m in &self.moves {
+            writeln!(
+                f,
+                "{:>4}  {:<6}{:>6}{:>6}{:>8}{:>8}{:>7}  {}",
+                m.ply,
+                format!("{:?}", m.color),
+                m.played.to_string(),
+                m.best.to_string(),
+                m.played_score,
+                m.best_score,
+                m.loss,
+                if m.verdict == Verdict::Best { String::new() } else { m.verdict.to_string() }
+            )?;
+        }
+        for (name, summary) in &[("Black", &self.black), ("White", &self.white)] {
+            writeln!(
+                f,
+                "{}: accuracy {:.1}% over {} moves, {} inaccuracies, {} mistakes, {} blunders, total loss {}",
+                name,
+                summary.accuracy,
+                summary.moves,
+                summary.inaccuracies,
+                summary.mistakes,
+                summary.blunders,
+                summary.total_loss
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub struct AnalyzeOptions {
+    // the transcript itself, or a file whose first non-empty line is the transcript
+    pub transcript: String,
+    // where the game started, if not from the usual starting position
+    pub position: Option<Position>,
+    pub depth: i32,
+    pub thresholds: Thresholds,
+    // where to write the review as JSON, "-" for standard output instead of the table
+    pub json: Option<String>,
+    // draw the position after every mistake and blunder
+    pub diagrams: bool,
+    pub renderer: Renderer,
+}
+
+impl Default for AnalyzeOptions {
+    fn default() -> AnalyzeOptions {
+        AnalyzeOptions {
+            transcript: String::new(),
+            position: None,
+            depth: 3,
+            thresholds: Thresholds::default(),
+            json: None,
+            diagrams: false,
+            renderer: Renderer::default(),
+        }
+    }
+}
+
+// A game given literally or in a file, with its moves played from `start` if there is one
+pub fn read_transcript(arg: &str, start: Option<&Position>) -> Result<Transcript, String> {
+    let text = if std::path::Path::new(arg).is_file() {
+        std::fs::read_to_string(arg).map_err(|e| format!("cannot read {}: {}", arg, e))?
+    } else {
+        arg.to_string()
+    };
+    let line = text.lines().map(|l| l.trim()).find(|l| !l.is_empty()).unwrap_or("");
+    // a saved game may have variations, which are left out
+    Ok(GameTree::parse_from(line, start)?.main_line())
+}
+
+// The games in a file, one transcript per line. Lines starting with # are comments.
+pub fn read_transcripts(path: &str) -> Result<Vec<Transcript>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| {
+            line.parse::<GameTree>()
+                .map(|game| game.main_line())
+                .map_err(|e| format!("{} line {}: {}", path, index + 1, e))
+        })
+        .collect()
+}
+
+// Draws the board after each mistake and blunder, with the move that was played marked
+pub fn print_diagrams(start: &Position, transcript: &Transcript, review: &GameReview, renderer: &Renderer) {
+    let mut board = Board::new_from(&start.board);
+    board.set_variant(transcript.variant);
+    for (m, &(color, played)) in review.moves.iter().zip(transcript.moves.iter()) {
+        let before = Board::new_from(&board);
+        board.place(played, color);
+        if m.verdict == Verdict::Mistake || m.verdict == Verdict::Blunder {
+            println!();
+            let (played, best) = (renderer.move_name(played), renderer.move_name(m.best));
+            println!("{}. {:?} {} is a {}, {} was better by {}", m.ply, color, played, m.verdict, best, m.loss);
+            renderer.print(&board, &Highlights::after_move(&before, &board, m.played, color));
+        }
+    }
+}
+
+pub fn run_analyze(options: &AnalyzeOptions, params: &EvalParams) -> Result<(), String> {
+    let transcript = read_transcript(&options.transcript, options.position.as_ref())?;
+    let mut stat = Stat::new();
+    let start = options
+        .position
+        .clone()
+        .or_else(|| transcript.start.clone())
+        .unwrap_or_else(Position::start);
+    let review = review_game(&start, &transcript, options.depth, params, &options.thresholds, &mut stat)?;
+
+    match options.json.as_deref() {
+        Some("-") => {}
+        _ => {
+            print!("{}", review);
+            if options.diagrams {
+                print_diagrams(&start, &transcript, &review, &options.renderer);
+            }
+        }
+    }
+    if let Some(path) = &options.json {
+        let json = serde_json::to_string_pretty(&review).map_err(|e| e.to_string())?;
+        if path == "-" {
+            println!("{}", json);
+        } else {
+            std::fs::write(path, json).map_err(|e| format!("cannot write {}: {}", path, e))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod test {
+
+    use crate::board::*;
+    use crate::color::*;
+    use crate::openings::cross_board;
+    use crate::params::EvalParams;
+    use crate::position::Position;
+    use crate::review::*;
+    use crate::stat::Stat;
+    use crate::transcript::*;
+
+    #[test]
+    fn test_classify() {
+        let thresholds = Thresholds::default();
+        assert_eq!(thresholds.classify(0), Verdict::Best);
+        assert_eq!(thresholds.classify(thresholds.inaccuracy), Verdict::Inaccuracy);
+        assert_eq!(thresholds.classify(thresholds.mistake), Verdict::Mistake);
+        assert_eq!(thresholds.classify(thresholds.blunder + 1), Verdict::Blunder);
+    }
+
+    #[test]
+    fn test_review_game() {
+        let transcript = Transcript::from_trace("bc4wc3bc2wb3ba4wd5");
+        let params = EvalParams::default();
+        let review = review_game(&Position::start(), &transcript, 1, &params, &Thresholds::default(), &mut Stat::new()).unwrap();
+
+        assert_eq!(review.moves.len(), 6);
+        assert_eq!(review.black.moves, 3);
+        assert_eq!(review.white.moves, 3);
+        for m in &review.moves {
+            assert!(m.played_score <= m.best_score);
+            assert_eq!(m.loss, m.best_score - m.played_score);
+            if m.played == m.best {
+                assert_eq!(m.loss, 0);
+            }
+        }
+        assert_eq!(review.moves[0].color, Color::Black);
+
+        let json = serde_json::to_value(&review).unwrap();
+        assert_eq!(json["moves"][0]["played"], "c4");
+        assert_eq!(json["moves"][1]["color"], "White");
+    }
+
+    #[test]
+    fn test_review_rejects_illegal_moves() {
+        let transcript = Transcript::from_trace("bc4wc4");
+        let params = EvalParams::default();
+        assert!(review_game(&Position::start(), &transcript, 1, &params, &Thresholds::default(), &mut Stat::new()).is_err());
+        let transcript = Transcript::from_trace("bc4");
+        assert!(review_game(&Position::start(), &transcript, 0, &params, &Thresholds::default(), &mut Stat::new()).is_err());
+        assert!(Transcript::parse("bc4wz9").is_err());
+        assert!(Transcript::parse("bc4w").is_err());
+        assert!(Transcript::parse("rules=anti bc4").is_err());
+    }
+
+    #[test]
+    fn test_read_transcript_from_a_start() {
+        // c5 is only legal from the cross start
+        let cross = Position::new(&cross_board(8), Color::Black);
+        assert!(read_transcript("bc5", None).is_err());
+        let transcript = read_transcript("bc5", Some(&cross)).unwrap();
+        assert_eq!(transcript.moves.len(), 1);
+        let review = review_game(&cross, &transcript, 1, &EvalParams::default(), &Thresholds::default(), &mut Stat::new());
+        assert!(review.is_ok());
+    }
+
+    #[test]
+    fn test_transcript_records_variant() {
+        let transcript = Transcript::from_trace("variant=anti bc4wc3");
+        assert_eq!(transcript.variant, Variant::Anti);
+        assert_eq!(transcript.moves.len(), 2);
+        assert_eq!(transcript.to_string(), "variant=anti bc4wc3");
+        assert_eq!(transcript.start_board().variant(), Variant::Anti);
+        assert_eq!(Transcript::from_trace("bc4").to_string(), "bc4");
+    }
+}
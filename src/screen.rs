@@ -0,0 +1,607 @@
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseButton, MouseEventKind};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use tui::backend::{Backend, CrosstermBackend};
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color as Paint, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, Wrap};
+use tui::{Frame, Terminal};
+
+use crate::analysis::*;
+use crate::board::*;
+use crate::book::Book;
+use crate::clock::*;
+use crate::color::Color;
+use crate::options::PlayOptions;
+use crate::params::EvalParams;
+use crate::player::*;
+use crate::position::Position;
+use crate::render::flipped_by;
+use crate::solve::final_score;
+use crate::stat::Stat;
+use crate::transcript::*;
+
+pub const KEYS_HELP: &str = "arrows/mouse: move  enter/space: play  u: undo  h: hint  n: new game  q: quit";
+
+// What the engine reported about its last search
+#[derive(Clone, Debug)]
+pub struct Thinking {
+    pub depth: i32,
+    pub nodes: u64,
+    pub score: i32,
+    pub pv: Vec<Pos2D>,
+}
+
+enum SearchUpdate {
+    // a depth was finished
+    Thinking(Thinking),
+    // the move to play and its score, which is None for a book move
+    Done(Option<(Pos2D, Option<i32>)>),
+}
+
+// The engine choosing its move on a thread of its own, the same way as at the prompt, so
+// that the screen and the clocks keep going. It stops when dropped.
+struct EngineSearch {
+    stop: Arc<AtomicBool>,
+    updates: Receiver<SearchUpdate>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl EngineSearch {
+    // Reports every depth that the search for the best move finishes, then the move
+    fn start(
+        position: Position,
+        settings: EngineSettings,
+        budget: Option<Duration>,
+        params: EvalParams,
+        book: Arc<Book>,
+        seed: u64) -> EngineSearch {
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, updates) = mpsc::channel();
+        let thread_stop = stop.clone();
+        let handle = thread::spawn(move || {
+            let mut stat = Stat::new();
+            stat.stop = Some(thread_stop);
+            let progress = sender.clone();
+            stat.progress = Some(Box::new(move |depth, score, line: &[Pos2D], nodes| {
+                let _ = progress.send(SearchUpdate::Thinking(Thinking { depth, nodes, score, pv: line.to_vec() }));
+            }));
+            let mut rng = StdRng::seed_from_u64(seed);
+            let found = engine_move(&position, &settings, budget, &params, &book, &mut rng, &mut stat);
+            let _ = sender.send(SearchUpdate::Done(found));
+        });
+        EngineSearch { stop, updates, handle: Some(handle) }
+    }
+}
+
+impl Drop for EngineSearch {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Action {
+    // move the cursor by this many columns and rows
+    Cursor(i32, i32),
+    Select,
+    // a mouse click at these terminal coordinates
+    Click(u16, u16),
+    Undo,
+    Hint,
+    NewGame,
+    Quit,
+}
+
+pub fn action_for(event: &Event) -> Option<Action> {
+    match event {
+        Event::Key(key) => match key.code {
+            KeyCode::Left => Some(Action::Cursor(-1, 0)),
+            KeyCode::Right => Some(Action::Cursor(1, 0)),
+            KeyCode::Up => Some(Action::Cursor(0, -1)),
+            KeyCode::Down => Some(Action::Cursor(0, 1)),
+            KeyCode::Enter | KeyCode::Char(' ') => Some(Action::Select),
+            KeyCode::Char('u') => Some(Action::Undo),
+            KeyCode::Char('h') => Some(Action::Hint),
+            KeyCode::Char('n') => Some(Action::NewGame),
+            KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+            _ => None,
+        },
+        Event::Mouse(mouse) => match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => Some(Action::Click(mouse.column, mouse.row)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// The state of a full-screen game, with a human or the engine on either side
+pub struct App {
+    pub start: Position,
+    pub variant: Variant,
+    pub board: Board,
+    pub transcript: Transcript,
+    pub to_move: Color,
+    pub players: Players,
+    // engines playing each other wait this long, or for enter, before each move
+    pub pause: Pause,
+    pub book: Arc<Book>,
+    // every engine move gets a seed from this, so that a seeded game can be replayed
+    pub rng: StdRng,
+    pub params: EvalParams,
+    pub cursor: Pos2D,
+    pub last_move: Option<Pos2D>,
+    // discs turned over by the last move
+    pub flipped: Vec<Pos2D>,
+    pub hint: Option<Pos2D>,
+    pub thinking: Option<Thinking>,
+    // time used by Black and White so far, not counting the move in progress
+    pub clocks: [Duration; 2],
+    pub turn_started: Instant,
+    // the time control, if any, and whether running out of time loses
+    pub clock: Clock,
+    pub lose_on_time: bool,
+    pub message: String,
+    pub game_over: bool,
+    pub quit: bool,
+    // the engine's search while it is thinking
+    search: Option<EngineSearch>,
+    // enter was pressed for the next move of engines playing each other
+    stepped: bool,
+    // where the board was last drawn, to find the cell under the mouse
+    board_area: Cell<Rect>,
+}
+
+fn clock_index(color: Color) -> usize {
+    if color == Color::Black { 0 } else { 1 }
+}
+
+fn format_clock(d: Duration) -> String {
+    format!("{}:{:02}", d.as_secs() / 60, d.as_secs() % 60)
+}
+
+impl App {
+    pub fn new(start: &Position, variant: Variant, params: &EvalParams) -> App {
+        let mut board = Board::new_from(&start.board);
+        board.set_variant(variant);
+        let mut transcript = Transcript::starting_at(start);
+        transcript.variant = variant;
+        let mut app = App {
+            start: start.clone(),
+            variant,
+            board,
+            transcript,
+            to_move: start.to_move,
+            players: Players::default(),
+            pause: Pause::None,
+            book: Arc::new(Book::builtin()),
+            rng: StdRng::from_entropy(),
+            params: params.clone(),
+            cursor: Pos2D::new(start.board.size() / 2 - 1, start.board.size() / 2 - 1),
+            last_move: None,
+            flipped: vec![],
+            hint: None,
+            thinking: None,
+            clocks: [Duration::from_secs(0); 2],
+            turn_started: Instant::now(),
+            clock: Clock::new(TimeControl::Unlimited),
+            lose_on_time: false,
+            message: String::new(),
+            game_over: false,
+            quit: false,
+            search: None,
+            stepped: false,
+            board_area: Cell::new(Rect::default()),
+        };
+        app.check_turn();
+        app
+    }
+
+    pub fn engine_to_move(&self) -> bool {
+        !self.game_over && matches!(self.players.of(self.to_move), Player::Engine(_))
+    }
+
+    // Whether an engine may start on its move, when engines playing each other pause
+    fn engine_may_start(&self) -> bool {
+        match self.pause {
+            _ if !self.players.engines_only() => true,
+            Pause::None => true,
+            Pause::Millis(ms) => self.turn_started.elapsed() >= Duration::from_millis(ms),
+            Pause::Step => self.stepped,
+        }
+    }
+
+    pub fn handle(&mut self, action: Action) {
+        match action {
+            Action::Cursor(di, dj) => {
+                let last = self.board.size() as i32 - 1;
+                self.cursor = Pos2D::new(
+                    (self.cursor.i as i32 + di).clamp(0, last) as usize,
+                    (self.cursor.j as i32 + dj).clamp(0, last) as usize,
+                );
+            }
+            Action::Select => {
+                if self.players.engines_only() {
+                    self.stepped = true;
+                    return;
+                }
+                if self.game_over || self.engine_to_move() {
+                    return;
+                }
+                if self.board.can_place(self.cursor, self.to_move) {
+                    self.play(self.cursor, "");
+                } else {
+                    self.message = format!("{} is not a legal move", self.cursor);
+                }
+            }
+            Action::Click(x, y) => {
+                if let Some(p) = self.cell_at(x, y) {
+                    self.cursor = p;
+                    self.handle(Action::Select);
+                }
+            }
+            Action::Undo => self.undo(),
+            Action::Hint => {
+                if !self.game_over && !self.engine_to_move() {
+                    // as at the prompt, a shallow search that counts discs near the end
+                    let hint_params = EvalParams { count_from: 58, ..self.params.clone() };
+                    if let Some((mv, score)) = minimax(&self.board, self.to_move, 3, &hint_params, &mut Stat::new()) {
+                        self.hint = Some(mv);
+                        self.message = format!("Hint: {} (score {})", mv, score);
+                    }
+                }
+            }
+            Action::NewGame => {
+                self.search = None;
+                let mut app = App::new(&self.start, self.variant, &self.params);
+                app.players = self.players.clone();
+                app.pause = self.pause;
+                app.book = self.book.clone();
+                app.rng = StdRng::seed_from_u64(self.rng.gen());
+                app.set_time_control(self.clock.control, self.lose_on_time);
+                *self = app;
+                self.message = String::from("New game");
+            }
+            Action::Quit => {
+                self.search = None;
+                self.quit = true;
+            }
+        }
+    }
+
+    // Gives both sides a fresh clock, started for the side to move
+    pub fn set_time_control(&mut self, control: TimeControl, lose_on_time: bool) {
+        self.clock = Clock::new(control);
+        self.lose_on_time = lose_on_time;
+        if !self.game_over {
+            self.clock.start(self.to_move);
+        }
+    }
+
+    // Ends the game if the side to move has run out of time and that loses
+    pub fn check_time(&mut self) {
+        if self.lose_on_time && !self.game_over && self.clock.out_of_time(self.to_move) {
+            self.search = None;
+            self.clock.stop();
+            self.game_over = true;
+            self.message = format!("{:?} ran out of time. {:?} wins", self.to_move, self.to_move.opposite());
+        }
+    }
+
+    pub fn engine_thinking(&self) -> bool {
+        self.search.is_some()
+    }
+
+    // Lets the engine start thinking if it is to move
+    pub fn start_engine(&mut self) {
+        if !self.engine_to_move() || self.search.is_some() || !self.engine_may_start() {
+            return;
+        }
+        if let Player::Engine(settings) = self.players.of(self.to_move) {
+            let position = Position::new(&self.board, self.to_move);
+            let budget = self.clock.budget(self.to_move, self.board.num_empty());
+            let params = settings.params.clone().unwrap_or_else(|| self.params.clone());
+            let (settings, seed) = (settings.clone(), self.rng.gen());
+            self.search = Some(EngineSearch::start(position, settings, budget, params, self.book.clone(), seed));
+            self.stepped = false;
+        }
+    }
+
+    // Takes in what the engine has found since the last call, and plays its move once it
+    // is done. Returns whether there was anything new.
+    pub fn poll_engine(&mut self) -> bool {
+        let mut changed = false;
+        while let Some(search) = &self.search {
+            match search.updates.try_recv() {
+                Ok(update) => self.update(update),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => self.search = None,
+            }
+            changed = true;
+        }
+        changed
+    }
+
+    // Lets the engine think and move, waiting for it, and calls `progress` after each
+    // depth it finishes
+    pub fn engine_move(&mut self, progress: &mut dyn FnMut(&App)) {
+        self.start_engine();
+        while let Some(search) = &self.search {
+            match search.updates.recv() {
+                Ok(SearchUpdate::Thinking(thinking)) => {
+                    self.update(SearchUpdate::Thinking(thinking));
+                    progress(self);
+                }
+                Ok(done) => self.update(done),
+                Err(_) => self.search = None,
+            }
+        }
+    }
+
+    fn update(&mut self, update: SearchUpdate) {
+        match update {
+            SearchUpdate::Thinking(thinking) => self.thinking = Some(thinking),
+            SearchUpdate::Done(found) => {
+                self.search = None;
+                match found {
+                    Some((mv, None)) => self.play(mv, " from the book"),
+                    Some((mv, Some(_))) => self.play(mv, ""),
+                    None => {}
+                }
+            }
+        }
+    }
+
+    // Plays the move, with `note` after it in the message
+    fn play(&mut self, mv: Pos2D, note: &str) {
+        let before = Board::new_from(&self.board);
+        self.board.place(mv, self.to_move);
+        self.transcript.add(mv, self.to_move);
+        self.flipped = flipped_by(&before, &self.board, mv);
+        self.last_move = Some(mv);
+        self.hint = None;
+        self.message = format!("{:?} played {}{}", self.to_move, mv, note);
+        self.clocks[clock_index(self.to_move)] += self.turn_started.elapsed();
+        self.clock.moved(self.to_move);
+        self.to_move = self.to_move.opposite();
+        self.check_turn();
+    }
+
+    // Hands the move over if the side to move has to pass, and notices the end of the game
+    fn check_turn(&mut self) {
+        self.turn_started = Instant::now();
+        if self.board.has_any_moves(self.to_move) {
+            self.clock.start(self.to_move);
+            return;
+        }
+        if self.board.has_any_moves(self.to_move.opposite()) {
+            self.message = format!("{:?} has to pass", self.to_move);
+            self.to_move = self.to_move.opposite();
+            self.clock.start(self.to_move);
+            return;
+        }
+        self.clock.stop();
+        self.game_over = true;
+        let score = final_score(&self.board, Color::Black);
+        let rule = if self.board.variant() == Variant::Anti { " with fewer discs" } else { "" };
+        self.message = if score > 0 {
+            format!("Game over. Black wins{} by {}", rule, score)
+        } else if score < 0 {
+            format!("Game over. White wins{} by {}", rule, -score)
+        } else {
+            String::from("Game over. It's a draw")
+        };
+    }
+
+    // Takes back moves up to and including the last one a human played
+    fn undo(&mut self) {
+        let players = self.players.clone();
+        let human = |color: Color| players.of(color) == &Player::Human;
+        if !self.transcript.moves.iter().any(|&(color, _)| human(color)) {
+            return;
+        }
+        // the engine may be thinking about the position being taken back
+        self.search = None;
+        while let Some((color, _)) = self.transcript.moves.pop() {
+            if human(color) {
+                break;
+            }
+        }
+        self.board = self.transcript.start_board();
+        let last_mover = self.board.replay_transcript(&self.transcript);
+        self.to_move = if self.transcript.moves.is_empty() { self.start.to_move } else { last_mover.opposite() };
+        self.last_move = self.transcript.moves.last().map(|&(_, mv)| mv);
+        self.flipped = vec![];
+        self.hint = None;
+        self.game_over = false;
+        self.message = String::from("Took back the last move");
+        self.check_turn();
+    }
+
+    fn cell_at(&self, x: u16, y: u16) -> Option<Pos2D> {
+        let area = self.board_area.get();
+        // three columns of row labels, then three columns per cell; one header line
+        if x < area.x + 3 || y < area.y + 1 {
+            return None;
+        }
+        let p = Pos2D::new(((x - area.x - 3) / 3) as usize, (y - area.y - 1) as usize);
+        if self.board.contains(p) { Some(p) } else { None }
+    }
+
+    fn cell_span(&self, p: Pos2D) -> Span<'static> {
+        let mut style = Style::default().bg(Paint::Green);
+        let text = if self.board.is_blocked(p) {
+            style = style.bg(Paint::DarkGray);
+            " # "
+        } else {
+            match self.board.get_at(p) {
+                Color::Black => {
+                    style = style.fg(Paint::Black);
+                    " ● "
+                }
+                Color::White => {
+                    style = style.fg(Paint::White);
+                    " ● "
+                }
+                Color::Empty if !self.game_over && self.board.can_place(p, self.to_move) => {
+                    style = style.fg(Paint::Yellow);
+                    " · "
+                }
+                Color::Empty => "   ",
+            }
+        };
+        if self.last_move == Some(p) {
+            style = style.bg(Paint::Red);
+        } else if self.flipped.contains(&p) {
+            style = style.bg(Paint::LightGreen);
+        }
+        if self.hint == Some(p) {
+            style = style.bg(Paint::Magenta);
+        }
+        if self.cursor == p {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        Span::styled(text, style)
+    }
+
+    fn board_lines(&self) -> Vec<Spans<'static>> {
+        let size = self.board.size();
+        let header: String = (0..size).map(|i| format!(" {} ", (b'a' + i as u8) as char)).collect();
+        let mut lines = vec![Spans::from(format!("   {}", header))];
+        for j in 0..size {
+            let mut spans = vec![Span::raw(format!("{:<3}", j))];
+            spans.extend((0..size).map(|i| self.cell_span(Pos2D::new(i, j))));
+            lines.push(Spans::from(spans));
+        }
+        lines
+    }
+
+    fn panel_lines(&self) -> Vec<Spans<'static>> {
+        let mut clocks = self.clocks;
+        if !self.game_over {
+            clocks[clock_index(self.to_move)] += self.turn_started.elapsed();
+        }
+        let mut lines = vec![
+            Spans::from(format!(
+                "Score   Black {}  White {}",
+                self.board.num_of_color(Color::Black),
+                self.board.num_of_color(Color::White)
+            )),
+            Spans::from(format!("Clocks  Black {}  White {}", format_clock(clocks[0]), format_clock(clocks[1]))),
+            Spans::from(match self.clock.control {
+                TimeControl::Unlimited => String::from("Time    no limit"),
+                _ => format!("Time    {}", self.clock),
+            }),
+            Spans::from(if self.game_over {
+                String::from("Game over")
+            } else {
+                format!("{:?} to move", self.to_move)
+            }),
+            Spans::from(""),
+        ];
+        match &self.thinking {
+            Some(t) => {
+                let pv: Vec<String> = t.pv.iter().map(|mv| mv.to_string()).collect();
+                lines.push(Spans::from(format!("Engine  depth {}  nodes {}  score {:+}", t.depth, t.nodes, t.score)));
+                lines.push(Spans::from(format!("PV      {}", pv.join(" "))));
+            }
+            None => lines.push(Spans::from("Engine  -")),
+        }
+        lines.push(Spans::from(""));
+        lines.push(Spans::from("Transcript"));
+        lines.push(Spans::from(self.transcript.to_string()));
+        lines
+    }
+
+    pub fn draw<B: Backend>(&self, f: &mut Frame<B>) {
+        let size = self.board.size() as u16;
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(size + 3), Constraint::Length(2)])
+            .split(f.size());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(size * 3 + 5), Constraint::Min(20)])
+            .split(rows[0]);
+
+        let board_block = Block::default().borders(Borders::ALL).title("Reversi");
+        self.board_area.set(board_block.inner(columns[0]));
+        f.render_widget(Paragraph::new(self.board_lines()).block(board_block), columns[0]);
+
+        let panel = Paragraph::new(self.panel_lines())
+            .block(Block::default().borders(Borders::ALL))
+            .wrap(Wrap { trim: false });
+        f.render_widget(panel, columns[1]);
+
+        let status = vec![Spans::from(self.message.clone()), Spans::from(KEYS_HELP)];
+        f.render_widget(Paragraph::new(status), rows[1]);
+    }
+}
+
+pub fn run_tui(options: &PlayOptions, params: &EvalParams) -> Result<(), String> {
+    // as at the prompt, everything random in the game comes from the seed
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let start = match &options.position {
+        Some(position) => position.clone(),
+        None => options.start.position(options.size, &mut rng)?,
+    };
+    let mut app = App::new(&start, options.variant, params);
+    app.players = options.players.clone();
+    app.pause = options.pause;
+    if let Some(path) = &options.book {
+        app.book = Arc::new(Book::load(path)?);
+    }
+    app.rng = rng;
+    app.set_time_control(options.time, options.lose_on_time);
+
+    enable_raw_mode().map_err(|e| e.to_string())?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture).map_err(|e| e.to_string())?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout)).map_err(|e| e.to_string())?;
+
+    let result = event_loop(&mut terminal, &mut app);
+
+    disable_raw_mode().map_err(|e| e.to_string())?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture).map_err(|e| e.to_string())?;
+    terminal.show_cursor().map_err(|e| e.to_string())?;
+    if result.is_ok() {
+        println!("{}", app.transcript);
+    }
+    result
+}
+
+fn event_loop<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<(), String> {
+    while !app.quit {
+        terminal.draw(|f| app.draw(f)).map_err(|e| e.to_string())?;
+        app.start_engine();
+        // wake up now and then to keep the clocks going and to show what the engine found
+        if event::poll(Duration::from_millis(100)).map_err(|e| e.to_string())? {
+            if let Some(action) = action_for(&event::read().map_err(|e| e.to_string())?) {
+                app.handle(action);
+            }
+        }
+        app.poll_engine();
+        app.check_time();
+    }
+    Ok(())
+}
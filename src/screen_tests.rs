@@ -0,0 +1,214 @@
+#[cfg(test)]
+mod test {
+
+    use tui::backend::TestBackend;
+    use tui::Terminal;
+
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::board::*;
+    use crate::clock::*;
+    use crate::color::*;
+    use crate::params::EvalParams;
+    use crate::player::*;
+    use crate::position::*;
+    use crate::screen::*;
+
+    fn engine(depth: i32) -> Player {
+        Player::Engine(EngineSettings { depth, ..EngineSettings::default() })
+    }
+
+    fn get_app() -> App {
+        let mut app = App::new(&Position::start(), Variant::Normal, &EvalParams::default());
+        app.players.white = engine(1);
+        app
+    }
+
+    fn render(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|f| app.draw(f)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let mut text = String::new();
+        for y in 0..buffer.area.height {
+            for x in 0..buffer.area.width {
+                text.push_str(&buffer.get(x, y).symbol);
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    #[test]
+    fn test_draw() {
+        let app = get_app();
+        let text = render(&app);
+        assert!(text.contains("a  b  c  d  e  f  g  h"));
+        assert!(text.contains("Score   Black 2  White 2"));
+        assert!(text.contains("Black to move"));
+        assert!(text.contains(KEYS_HELP));
+        // the four legal moves are marked
+        assert_eq!(text.matches('·').count(), 4);
+    }
+
+    #[test]
+    fn test_play_with_keys_and_undo() {
+        let mut app = get_app();
+        // the cursor starts on d3; c3 is not legal, c4 is
+        app.handle(Action::Cursor(-1, 0));
+        app.handle(Action::Select);
+        assert!(app.transcript.moves.is_empty());
+        app.handle(Action::Cursor(0, 1));
+        app.handle(Action::Select);
+        assert_eq!(app.transcript.moves.len(), 1);
+        assert_eq!(app.last_move, Some(Pos2D::new(2, 4)));
+        assert_eq!(app.flipped, vec![Pos2D::new(3, 4)]);
+        assert!(app.engine_to_move());
+
+        let mut iterations = 0;
+        app.engine_move(&mut |_| iterations += 1);
+        assert_eq!(iterations, 1);
+        assert_eq!(app.transcript.moves.len(), 2);
+        assert_eq!(app.to_move, Color::Black);
+        assert!(app.thinking.as_ref().unwrap().pv.len() <= 2);
+        assert!(render(&app).contains("Engine  depth 1"));
+
+        app.handle(Action::Undo);
+        assert!(app.transcript.moves.is_empty());
+        assert_eq!(app.board.to_string(), Board::new_start().to_string());
+        assert_eq!(app.to_move, Color::Black);
+    }
+
+    #[test]
+    fn test_engine_thinks_in_the_background() {
+        let mut app = get_app();
+        app.handle(Action::Cursor(-1, 1));
+        app.handle(Action::Select);
+        app.start_engine();
+        assert!(app.engine_thinking());
+        // the human can't move meanwhile, but the screen still draws
+        app.handle(Action::Select);
+        assert_eq!(app.transcript.moves.len(), 1);
+        assert!(render(&app).contains("White to move"));
+        for _ in 0..500 {
+            if !app.poll_engine() {
+                thread::sleep(Duration::from_millis(10));
+            }
+            if !app.engine_thinking() {
+                break;
+            }
+        }
+        assert_eq!(app.transcript.moves.len(), 2);
+        assert_eq!(app.to_move, Color::Black);
+
+        // taking back the move stops a search that is under way
+        app.players.white = engine(20);
+        app.cursor = app.board.get_available_moves_for(Color::Black).next().unwrap();
+        app.handle(Action::Select);
+        app.start_engine();
+        app.handle(Action::Undo);
+        assert!(!app.engine_thinking());
+        assert_eq!(app.transcript.moves.len(), 2);
+        assert_eq!(app.to_move, Color::Black);
+    }
+
+    #[test]
+    fn test_time_control() {
+        let mut app = get_app();
+        app.set_time_control(TimeControl::PerMove(Duration::from_millis(100)), true);
+        assert!(render(&app).contains("Time    Black"));
+        app.handle(Action::Cursor(-1, 1));
+        app.handle(Action::Select);
+        // the engine keeps within its time for the move
+        app.players.white = engine(20);
+        app.engine_move(&mut |_| {});
+        app.check_time();
+        assert_eq!(app.transcript.moves.len(), 2);
+        assert!(!app.game_over);
+
+        thread::sleep(Duration::from_millis(110));
+        app.check_time();
+        assert!(app.game_over);
+        assert_eq!(app.message, "Black ran out of time. White wins");
+    }
+
+    #[test]
+    fn test_mouse_hint_and_new_game() {
+        let mut app = get_app();
+        render(&app);
+        // the board starts inside the border, after the header line and the row labels:
+        // cell f3 is at column 1 + 3 + 5 * 3, row 1 + 1 + 3
+        app.handle(Action::Click(1 + 3 + 5 * 3, 1 + 1 + 3));
+        assert_eq!(app.transcript.moves.len(), 1);
+        assert_eq!(app.last_move, Some(Pos2D::new(5, 3)));
+        app.handle(Action::Click(0, 0));
+        assert_eq!(app.transcript.moves.len(), 1);
+
+        app.engine_move(&mut |_| {});
+        app.handle(Action::Hint);
+        assert!(app.board.can_place(app.hint.unwrap(), Color::Black));
+
+        app.handle(Action::NewGame);
+        assert!(app.transcript.moves.is_empty());
+        assert_eq!(app.players.white, engine(1));
+        app.handle(Action::Quit);
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn test_hot_seat() {
+        let mut app = get_app();
+        app.players.white = Player::Human;
+        assert!(!app.engine_to_move());
+        app.handle(Action::Cursor(-1, 1));
+        app.handle(Action::Select);
+        app.start_engine();
+        assert!(!app.engine_thinking());
+        // White's turn is played with the keys as well
+        app.cursor = app.board.get_available_moves_for(Color::White).next().unwrap();
+        app.handle(Action::Select);
+        assert_eq!(app.transcript.moves.len(), 2);
+        // undo takes back one move, as both sides are human
+        app.handle(Action::Undo);
+        assert_eq!(app.transcript.moves.len(), 1);
+        assert_eq!(app.to_move, Color::White);
+    }
+
+    #[test]
+    fn test_engines_play_each_other() {
+        let mut app = get_app();
+        app.players.black = engine(1);
+        app.pause = Pause::Step;
+        app.start_engine();
+        assert!(!app.engine_thinking());
+        // enter lets the next move go ahead
+        app.handle(Action::Select);
+        app.engine_move(&mut |_| {});
+        assert_eq!(app.transcript.moves.len(), 1);
+        app.engine_move(&mut |_| {});
+        assert_eq!(app.transcript.moves.len(), 1);
+        app.handle(Action::Select);
+        app.engine_move(&mut |_| {});
+        assert_eq!(app.transcript.moves.len(), 2);
+        assert_eq!(app.to_move, Color::Black);
+    }
+
+    #[test]
+    fn test_engine_plays_its_level() {
+        let mut app = get_app();
+        app.players.white = Player::Engine(EngineSettings::level("easy").unwrap());
+        app.handle(Action::Cursor(-1, 1));
+        app.handle(Action::Select);
+        app.engine_move(&mut |_| {});
+        assert_eq!(app.transcript.moves.len(), 2);
+        assert!(app.message.ends_with("from the book"), "{}", app.message);
+    }
+
+    #[test]
+    fn test_anti_reversi_result() {
+        let board: Board = "XOOOOOOOOOOOOOOO".parse().unwrap();
+        let app = App::new(&Position::new(&board, Color::Black), Variant::Anti, &EvalParams::default());
+        assert!(app.game_over);
+        assert_eq!(app.message, "Game over. Black wins with fewer discs by 14");
+    }
+}
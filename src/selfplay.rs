@@ -0,0 +1,118 @@
+#![allow(dead_code)]
+
+use rand::seq::IteratorRandom;
+use rand::Rng;
+
+use crate::analysis::*;
+use crate::board::*;
+use crate::color::Color;
+use crate::openings::Openings;
+use crate::params::EvalParams;
+use crate::stat::Stat;
+use crate::transcript::*;
+
+// Plays random legal moves from the starting position. Stops early if the game ends.
+pub fn random_opening<R: Rng>(plies: usize, rng: &mut R) -> Transcript {
+    let mut board = Board::new_start();
+    let mut transcript = Transcript::new();
+    let mut color = Color::Black;
+    for _ in 0..plies {
+        if !board.has_any_moves(color) {
+            color = color.opposite();
+            if !board.has_any_moves(color) {
+                break;
+            }
+        }
+        let mv = board.get_available_moves_for(color).choose(rng).unwrap();
+        board.place(mv, color);
+        transcript.add(mv, color);
+        color = color.opposite();
+    }
+    transcript
+}
+
+// Continues the game in `opening` to the end, each side searching with its own parameters.
+// Returns the complete transcript and the final board.
+pub fn play_out(
+    opening: &Transcript,
+    black: &EvalParams,
+    white: &EvalParams,
+    depth: i32) -> Result<(Transcript, Board), String> {
+
+    let mut board = opening.start_board();
+    let mut transcript = Transcript::new();
+    transcript.variant = opening.variant;
+    transcript.start = opening.start.clone();
+    let mut color = opening.first_mover();
+    for mv in &opening.moves {
+        board.place(mv.1, mv.0);
+        transcript.add(mv.1, mv.0);
+        color = mv.0.opposite();
+    }
+
+    loop {
+        if !board.has_any_moves(color) {
+            color = color.opposite();
+            if !board.has_any_moves(color) {
+                break;
+            }
+        }
+        let params = if color == Color::Black { black } else { white };
+        let (pos, _) =
+            negamax(&board, color, depth, params, &mut Stat::new()).ok_or_else(|| format!("cannot search {} moves deep", depth))?;
+        board.place(pos, color);
+        transcript.add(pos, color);
+        color = color.opposite();
+    }
+    Ok((transcript, board))
+}
+
+pub struct MatchResult {
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+}
+
+impl MatchResult {
+    // Fraction of the available points scored by the first player, counting draws as half
+    pub fn score(&self) -> f64 {
+        let games = self.wins + self.losses + self.draws;
+        if games == 0 {
+            return 0.5;
+        }
+        (self.wins as f64 + self.draws as f64 / 2.0) / games as f64
+    }
+}
+
+// Plays `pairs` pairs of games between two parameter sets. Both games of a pair start
+// from the same opening, with colors swapped, so neither side profits from it.
+pub fn play_match<R: Rng>(
+    first: &EvalParams,
+    second: &EvalParams,
+    pairs: usize,
+    openings: &Openings,
+    depth: i32,
+    rng: &mut R) -> Result<MatchResult, String> {
+
+    let mut result = MatchResult { wins: 0, losses: 0, draws: 0 };
+    for _ in 0..pairs {
+        let opening = openings.pick(rng);
+        for &first_color in &[Color::Black, Color::White] {
+            let (_, board) = if first_color == Color::Black {
+                play_out(&opening, first, second, depth)?
+            } else {
+                play_out(&opening, second, first, depth)?
+            };
+            let own = board.num_of_color(first_color);
+            let opponent = board.num_of_color(first_color.opposite());
+            if own > opponent {
+                result.wins += 1;
+            } else if own < opponent {
+                result.losses += 1;
+            } else {
+                result.draws += 1;
+            }
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,376 @@
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::analysis::*;
+use crate::board::*;
+use crate::color::Color;
+use crate::gametree::GameTree;
+use crate::params::EvalParams;
+use crate::position::Position;
+use crate::render::flipped_by;
+use crate::review::*;
+use crate::stat::Stat;
+use crate::transcript::*;
+
+// Limits that keep one client from tying up the machine
+const MAX_DEPTH: i32 = 10;
+const MAX_TIME: Duration = Duration::from_secs(60);
+// for requests without "time_ms"
+const DEFAULT_TIME: Duration = Duration::from_secs(10);
+const MAX_LINES: usize = 64;
+const MAX_BODY: usize = 64 * 1024;
+// for the request line and each header line, and the number of headers
+const MAX_LINE: usize = 8 * 1024;
+const MAX_HEADERS: usize = 100;
+const MAX_CONNECTIONS: usize = 8;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+// The body of every request. The game is given either as a position or as a transcript,
+// which may have variations; without either it is the usual start.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiRequest {
+    pub position: Option<String>,
+    pub transcript: Option<String>,
+    #[serde(rename = "move")]
+    pub mv: Option<String>,
+    pub depth: Option<i32>,
+    pub time_ms: Option<u64>,
+    pub lines: Option<usize>,
+}
+
+// Where a game stands. `to_move` is the side that moves next, after a pass if the other
+// side has no move, and None once the game is over.
+#[derive(Serialize)]
+pub struct GameState {
+    pub position: String,
+    pub transcript: String,
+    pub to_move: Option<Color>,
+    pub moves: Vec<Pos2D>,
+    pub black: usize,
+    pub white: usize,
+    pub game_over: bool,
+    // the discs the move turned over, for /play
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub flipped: Vec<Pos2D>,
+}
+
+#[derive(Serialize)]
+pub struct BestMove {
+    #[serde(rename = "move")]
+    pub mv: Pos2D,
+    pub score: i32,
+    pub nodes: u64,
+}
+
+#[derive(Serialize)]
+pub struct Line {
+    #[serde(rename = "move")]
+    pub mv: Pos2D,
+    pub score: i32,
+    pub pv: Vec<Pos2D>,
+}
+
+#[derive(Serialize)]
+pub struct Analysis {
+    pub depth: i32,
+    pub lines: Vec<Line>,
+}
+
+#[derive(Serialize)]
+struct ApiError {
+    error: String,
+}
+
+fn game_of(request: &ApiRequest) -> Result<GameTree, String> {
+    match (&request.transcript, &request.position) {
+        (Some(_), Some(_)) => Err(String::from("give a position or a transcript, not both")),
+        (Some(transcript), None) => transcript.parse(),
+        (None, Some(position)) => Ok(GameTree::new(&Transcript::starting_at(&position.parse::<Position>()?))),
+        (None, None) => Ok(GameTree::new(&Transcript::new())),
+    }
+}
+
+// The side that moves next on `board`, None if neither can
+fn next_mover(board: &Board, to_move: Color) -> Option<Color> {
+    [to_move, to_move.opposite()].iter().copied().find(|&color| board.has_any_moves(color))
+}
+
+fn state_of(game: &GameTree, flipped: Vec<Pos2D>) -> GameState {
+    let board = game.board();
+    let to_move = next_mover(&board, game.to_move());
+    GameState {
+        position: Position::new(&board, to_move.unwrap_or_else(|| game.to_move())).to_string(),
+        transcript: game.to_string(),
+        to_move,
+        moves: to_move.map_or(vec![], |color| board.get_available_moves_for(color).collect()),
+        black: board.num_of_color(Color::Black),
+        white: board.num_of_color(Color::White),
+        game_over: to_move.is_none(),
+        flipped,
+    }
+}
+
+fn depth_of(request: &ApiRequest, default: i32) -> Result<i32, String> {
+    match request.depth.unwrap_or(default) {
+        depth if (1..=MAX_DEPTH).contains(&depth) => Ok(depth),
+        depth => Err(format!("depth {} is not between 1 and {}", depth, MAX_DEPTH)),
+    }
+}
+
+// How long the searches for a request may take in all
+fn budget_of(request: &ApiRequest) -> Duration {
+    request.time_ms.map_or(DEFAULT_TIME, Duration::from_millis).min(MAX_TIME)
+}
+
+// A search that gives up once the time for the request is up
+fn stat_for(request: &ApiRequest) -> Stat {
+    let mut stat = Stat::new();
+    stat.deadline = Some(Instant::now() + budget_of(request));
+    stat
+}
+
+fn out_of_time(request: &ApiRequest) -> String {
+    format!("the search took longer than {} ms, try a smaller depth", budget_of(request).as_millis())
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+fn api_moves(request: &ApiRequest) -> Result<String, String> {
+    Ok(to_json(&state_of(&game_of(request)?, vec![])))
+}
+
+fn api_play(request: &ApiRequest) -> Result<String, String> {
+    let mut game = game_of(request)?;
+    let board = game.board();
+    let mover = next_mover(&board, game.to_move()).ok_or("the game is over")?;
+    let mv = request.mv.as_deref().ok_or("which move?")?;
+    let p = Pos2D::parse(mv).filter(|&p| board.can_place(p, mover));
+    let p = p.ok_or_else(|| format!("{} is not a legal move for {:?}", mv, mover))?;
+    game.play(mover, p);
+    let flipped = flipped_by(&board, &game.board(), p);
+    Ok(to_json(&state_of(&game, flipped)))
+}
+
+fn api_best(request: &ApiRequest, params: &EvalParams) -> Result<String, String> {
+    let game = game_of(request)?;
+    let board = game.board();
+    let color = next_mover(&board, game.to_move()).ok_or("the game is over")?;
+    let depth = depth_of(request, 6)?;
+    let mut stat = Stat::new();
+    // as deep as the time allows, up to the depth
+    let (mv, score) = negamax_timed(&board, color, depth, budget_of(request), params, &mut stat).ok_or("no move")?;
+    Ok(to_json(&BestMove { mv, score, nodes: stat.nodes_viewed }))
+}
+
+fn api_analyze(request: &ApiRequest, params: &EvalParams) -> Result<String, String> {
+    let game = game_of(request)?;
+    let board = game.board();
+    let color = next_mover(&board, game.to_move()).ok_or("the game is over")?;
+    let depth = depth_of(request, 3)?;
+    let count = request.lines.unwrap_or(3).clamp(1, MAX_LINES);
+    let mut stat = stat_for(request);
+    let lines = multi_pv(&board, color, depth, count, params, &mut stat);
+    if stat.aborted {
+        return Err(out_of_time(request));
+    }
+    let lines = lines
+        .into_iter()
+        .map(|(score, pv)| Line { mv: pv[0], score, pv })
+        .collect();
+    Ok(to_json(&Analysis { depth, lines }))
+}
+
+fn api_review(request: &ApiRequest, params: &EvalParams) -> Result<String, String> {
+    let transcript = request.transcript.as_deref().ok_or("review needs a transcript")?;
+    let transcript = transcript.parse::<GameTree>()?.main_line();
+    let start = transcript.start.clone().unwrap_or_else(Position::start);
+    let depth = depth_of(request, 3)?;
+    let mut stat = stat_for(request);
+    let review = review_game(&start, &transcript, depth, params, &Thresholds::default(), &mut stat);
+    if stat.aborted {
+        return Err(out_of_time(request));
+    }
+    Ok(to_json(&review?))
+}
+
+// Answers one request with a status code and a JSON body. Everything is worked out from
+// the request alone, so requests can be answered side by side.
+pub fn handle(method: &str, path: &str, body: &str, params: &EvalParams) -> (u16, String) {
+    let api: fn(&ApiRequest, &EvalParams) -> Result<String, String> = match path {
+        "/moves" => |request, _| api_moves(request),
+        "/play" => |request, _| api_play(request),
+        "/best" => api_best,
+        "/analyze" => api_analyze,
+        "/review" => api_review,
+        _ => return (404, to_json(&ApiError { error: format!("no such endpoint {}", path) })),
+    };
+    if method != "POST" {
+        return (405, to_json(&ApiError { error: format!("use POST for {}", path) }));
+    }
+    let request = if body.trim().is_empty() {
+        Ok(ApiRequest::default())
+    } else {
+        serde_json::from_str(body).map_err(|e| format!("bad request: {}", e))
+    };
+    match request.and_then(|request| api(&request, params)) {
+        Ok(json) => (200, json),
+        Err(error) => (400, to_json(&ApiError { error })),
+    }
+}
+
+// One line of at most MAX_LINE bytes, or None if it is longer
+fn read_line<R: BufRead>(reader: &mut R) -> Result<Option<String>, String> {
+    let mut line = String::new();
+    reader.take(MAX_LINE as u64 + 1).read_line(&mut line).map_err(|e| e.to_string())?;
+    Ok(if line.len() > MAX_LINE { None } else { Some(line) })
+}
+
+// The method, the path and the body of an HTTP request, or the status and the error to
+// answer with
+fn read_request(stream: &TcpStream) -> Result<(String, String, String), (u16, String)> {
+    let bad = |error: String| (400, error);
+    let mut reader = BufReader::new(stream);
+    let line = read_line(&mut reader).map_err(bad)?.ok_or((400, String::from("request line too long")))?;
+    let mut words = line.split_whitespace();
+    let (method, path) = match (words.next(), words.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(bad(String::from("bad request line"))),
+    };
+    let mut length = 0;
+    let mut headers = 0;
+    loop {
+        let line = read_line(&mut reader).map_err(bad)?.ok_or((431, String::from("header line too long")))?;
+        let header = line.trim();
+        if header.is_empty() {
+            break;
+        }
+        headers += 1;
+        if headers > MAX_HEADERS {
+            return Err((431, String::from("too many headers")));
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().map_err(|_| bad(String::from("bad content length")))?;
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err(bad(String::from("request too large")));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(|e| bad(e.to_string()))?;
+    let body = String::from_utf8(body).map_err(|_| bad(String::from("the body is not UTF-8")))?;
+    Ok((method, path, body))
+}
+
+fn respond(mut stream: &TcpStream, status: u16, body: &str) {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        431 => "Request Header Fields Too Large",
+        _ => "Service Unavailable",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    // the client may be gone, and there is nobody else to tell
+    let _ = stream.write_all(response.as_bytes());
+}
+
+fn serve_connection(stream: TcpStream, params: &EvalParams) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    match read_request(&stream) {
+        Ok((method, path, body)) => {
+            let (status, json) = handle(&method, &path, &body, params);
+            respond(&stream, status, &json);
+        }
+        Err((status, error)) => {
+            respond(&stream, status, &to_json(&ApiError { error }));
+            // read what is left of the request, up to a point, so that closing with it
+            // unread doesn't reset the connection before the client sees the answer
+            let _ = stream.shutdown(Shutdown::Write);
+            let _ = io::copy(&mut (&stream).take(MAX_BODY as u64), &mut io::sink());
+        }
+    }
+}
+
+// Counts a connection as active for as long as it lives
+struct Active(Arc<AtomicUsize>);
+
+impl Drop for Active {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// An HTTP server answering JSON requests about positions and games, each connection on
+// a thread of its own
+pub struct Server {
+    listener: TcpListener,
+    params: Arc<EvalParams>,
+    active: Arc<AtomicUsize>,
+}
+
+impl Server {
+    pub fn bind(address: &str, params: &EvalParams) -> Result<Server, String> {
+        let listener = TcpListener::bind(address).map_err(|e| format!("cannot listen on {}: {}", address, e))?;
+        Ok(Server { listener, params: Arc::new(params.clone()), active: Arc::new(AtomicUsize::new(0)) })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.listener.local_addr().unwrap()
+    }
+
+    // Serves until the process ends
+    pub fn run(&self) {
+        for stream in self.listener.incoming().flatten() {
+            if self.active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                self.active.fetch_sub(1, Ordering::SeqCst);
+                // turned away without reading the request, so that the loop never waits on it
+                respond(&stream, 503, &to_json(&ApiError { error: String::from("too busy, try again") }));
+                let _ = stream.shutdown(Shutdown::Write);
+                continue;
+            }
+            let active = Active(self.active.clone());
+            let params = self.params.clone();
+            thread::spawn(move || {
+                serve_connection(stream, &params);
+                drop(active);
+            });
+        }
+    }
+}
+
+pub struct ServeOptions {
+    pub port: u16,
+}
+
+impl Default for ServeOptions {
+    fn default() -> ServeOptions {
+        ServeOptions { port: 8080 }
+    }
+}
+
+pub fn run_serve(options: &ServeOptions, params: &EvalParams) -> Result<(), String> {
+    // only for this machine: the front end runs next to it
+    let server = Server::bind(&format!("127.0.0.1:{}", options.port), params)?;
+    println!("Serving on http://{}, POST to /moves, /play, /best, /analyze or /review", server.local_addr());
+    server.run();
+    Ok(())
+}
@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod test {
+
+    use serde_json::Value;
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+    use std::thread;
+
+    use crate::analysis::*;
+    use crate::board::*;
+    use crate::color::*;
+    use crate::params::EvalParams;
+    use crate::server::*;
+    use crate::stat::Stat;
+
+    fn start_server() -> SocketAddr {
+        let server = Server::bind("127.0.0.1:0", &EvalParams::default()).unwrap();
+        let address = server.local_addr();
+        thread::spawn(move || server.run());
+        address
+    }
+
+    // Sends one request over a new connection and returns the status and the JSON body
+    fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}", method, path, body.len(), body).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response.split_whitespace().nth(1).unwrap().parse().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn test_moves_and_play() {
+        let address = start_server();
+        let (status, state) = request(address, "POST", "/moves", "");
+        assert_eq!(status, 200);
+        assert_eq!(state["to_move"], "Black");
+        assert_eq!(state["moves"].as_array().unwrap().len(), 4);
+
+        let (status, state) = request(address, "POST", "/play", r#"{"move": "c4"}"#);
+        assert_eq!(status, 200);
+        assert_eq!(state["transcript"], "bc4");
+        assert_eq!(state["flipped"], serde_json::json!(["d4"]));
+        assert_eq!((state["black"].as_u64(), state["white"].as_u64()), (Some(4), Some(1)));
+
+        // the game goes on from the position that came back
+        let body = format!(r#"{{"position": "{}", "move": "c5"}}"#, state["position"].as_str().unwrap());
+        let (status, state) = request(address, "POST", "/play", &body);
+        assert_eq!(status, 200);
+        assert_eq!(state["to_move"], "Black");
+
+        let (status, error) = request(address, "POST", "/play", r#"{"transcript": "bc4", "move": "a0"}"#);
+        assert_eq!(status, 400);
+        assert_eq!(error["error"], "a0 is not a legal move for White");
+    }
+
+    #[test]
+    fn test_best_and_analyze() {
+        let address = start_server();
+        let (pos, score) = negamax(&Board::new_start(), Color::Black, 2, &EvalParams::default(), &mut Stat::new()).unwrap();
+        let (status, best) = request(address, "POST", "/best", r#"{"depth": 2}"#);
+        assert_eq!(status, 200);
+        assert_eq!((best["move"].as_str(), best["score"].as_i64()), (Some(pos.to_string().as_str()), Some(score as i64)));
+
+        let (status, best) = request(address, "POST", "/best", r#"{"depth": 4, "time_ms": 0}"#);
+        assert_eq!(status, 200);
+        assert!(best["move"].is_string());
+
+        let (status, analysis) = request(address, "POST", "/analyze", r#"{"depth": 2, "lines": 2}"#);
+        assert_eq!(status, 200);
+        let lines = analysis["lines"].as_array().unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["score"].as_i64(), Some(score as i64));
+        assert_eq!(lines[0]["pv"][0], lines[0]["move"]);
+
+        let (status, _) = request(address, "POST", "/best", r#"{"depth": 99}"#);
+        assert_eq!(status, 400);
+
+        // every request has a time limit, and deep analysis runs out of it
+        let (status, error) = request(address, "POST", "/analyze", r#"{"depth": 10, "time_ms": 50}"#);
+        assert_eq!(status, 400);
+        assert_eq!(error["error"], "the search took longer than 50 ms, try a smaller depth");
+    }
+
+    #[test]
+    fn test_review() {
+        let address = start_server();
+        let (status, review) = request(address, "POST", "/review", r#"{"transcript": "bc4wc5bf3", "depth": 1}"#);
+        assert_eq!(status, 200);
+        assert_eq!(review["moves"].as_array().unwrap().len(), 3);
+        assert_eq!(review["moves"][1]["played"], "c5");
+
+        let (status, _) = request(address, "POST", "/review", "");
+        assert_eq!(status, 400);
+    }
+
+    #[test]
+    fn test_bad_requests() {
+        let address = start_server();
+        assert_eq!(request(address, "POST", "/nowhere", "").0, 404);
+        assert_eq!(request(address, "GET", "/moves", "").0, 405);
+        assert_eq!(request(address, "POST", "/moves", "{not json").0, 400);
+        assert_eq!(request(address, "POST", "/moves", r#"{"colour": "black"}"#).0, 400);
+        assert_eq!(request(address, "POST", "/moves", r#"{"position": "X"}"#).0, 400);
+        assert_eq!(request(address, "POST", "/moves", r#"{"transcript": "bc4bc4"}"#).0, 400);
+        assert_eq!(request(address, "POST", "/review", r#"{"transcript": "bc4wa0"}"#).0, 400);
+    }
+
+    // Sends the raw text of a request and returns the status of the answer
+    fn raw_request(address: SocketAddr, text: &str) -> u16 {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(text.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response.split_whitespace().nth(1).unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn test_limits_on_the_request_head() {
+        let address = start_server();
+        let long_path = format!("POST /{} HTTP/1.1\r\n\r\n", "x".repeat(10_000));
+        assert_eq!(raw_request(address, &long_path), 400);
+        let long_header = format!("POST /moves HTTP/1.1\r\nX-Long: {}\r\n\r\n", "x".repeat(10_000));
+        assert_eq!(raw_request(address, &long_header), 431);
+        let many_headers = format!("POST /moves HTTP/1.1\r\n{}\r\n", "X-Header: 1\r\n".repeat(200));
+        assert_eq!(raw_request(address, &many_headers), 431);
+        assert_eq!(raw_request(address, "POST /moves HTTP/1.1\r\nContent-Length: 0\r\n\r\n"), 200);
+    }
+
+    #[test]
+    fn test_concurrent_requests() {
+        let address = start_server();
+        let clients: Vec<_> = (0..6)
+            .map(|_| thread::spawn(move || request(address, "POST", "/best", r#"{"depth": 3}"#)))
+            .collect();
+        let answers: Vec<(u16, Value)> = clients.into_iter().map(|c| c.join().unwrap()).collect();
+        assert!(answers.iter().all(|(status, _)| *status == 200));
+        assert!(answers.iter().all(|(_, best)| best["move"] == answers[0].1["move"]));
+    }
+}
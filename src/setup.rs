@@ -0,0 +1,207 @@
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::{self, Write};
+
+use crate::board::*;
+use crate::color::Color;
+use crate::explain::*;
+use crate::params::EvalParams;
+use crate::position::Position;
+
+pub const SETUP_HELP: &str = "\
+Setup commands:
+  b <cell>...         put black discs, e.g. b c4 d5
+  w <cell>...         put white discs
+  x <cell>...         remove discs or holes
+  hole <cell>...      block cells, so that no disc can go there
+  clear               empty the board, holes included
+  start               reset to the starting position
+  side <b|w>          set the side to move
+  load <pos|file>     load a position (the cells and X or O to move), or a file with one
+  save [file]         show the position, or write it to a file
+  eval                show the evaluation for both sides
+  done                check the position and play from it
+  cancel              leave setup without changes";
+
+// A position being edited: the board and whose turn it is
+pub struct Setup {
+    pub board: Board,
+    pub to_move: Color,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum SetupOutcome {
+    Continue,
+    Done,
+    Cancel,
+}
+
+// Checks that the position could have come up in a real game. Returns the reasons
+// it could not, or otherwise warnings about things that are merely unusual.
+pub fn validate_position(board: &Board, to_move: Color) -> Result<Vec<String>, String> {
+    let size = board.size();
+    let h = size / 2;
+    let center = [(h - 1, h - 1), (h - 1, h), (h, h - 1), (h, h)];
+    if center.iter().any(|&(i, j)| board.get_at(Pos2D::new(i, j)) == Color::Empty) {
+        return Err(String::from("the four center cells must be occupied"));
+    }
+
+    // Every disc is placed next to an existing one, so all discs are connected to the center
+    let mut reached = vec![false; size * size];
+    let mut pending: Vec<(usize, usize)> = center.to_vec();
+    for &(i, j) in &center {
+        reached[j * size + i] = true;
+    }
+    while let Some((i, j)) = pending.pop() {
+        for di in -1..=1i32 {
+            for dj in -1..=1i32 {
+                let ni = (i as i32 + di) as usize;
+                let nj = (j as i32 + dj) as usize;
+                if ni < size && nj < size && !reached[nj * size + ni] && board.get_at(Pos2D::new(ni, nj)) != Color::Empty {
+                    reached[nj * size + ni] = true;
+                    pending.push((ni, nj));
+                }
+            }
+        }
+    }
+    for (p, reached) in board.cells().zip(reached.iter()) {
+        if board.get_at(p) != Color::Empty && !reached {
+            return Err(format!("the disc at {} is not connected to the others", p));
+        }
+    }
+
+    if !board.has_any_moves(Color::Black) && !board.has_any_moves(Color::White) {
+        return Err(String::from("neither side can move, the game is already over"));
+    }
+
+    let mut warnings = vec![];
+    if !board.has_any_moves(to_move) {
+        warnings.push(format!("{:?} has no moves and will have to pass", to_move));
+    }
+    // Without passes, Black moves whenever an even number of discs has been added
+    let expected = if (board.num_occupied() - 4).is_multiple_of(2) { Color::Black } else { Color::White };
+    if expected != to_move {
+        warnings.push(format!("{:?} to move means somebody must have passed before", to_move));
+    }
+    Ok(warnings)
+}
+
+fn parse_cells(board: &Board, args: &[&str]) -> Result<Vec<Pos2D>, String> {
+    if args.is_empty() {
+        return Err(String::from("which cells?"));
+    }
+    args.iter()
+        .map(|a| {
+            Pos2D::parse(a)
+                .filter(|p| board.contains(*p))
+                .ok_or_else(|| format!("'{}' is not a cell", a))
+        })
+        .collect()
+}
+
+impl Setup {
+    pub fn new(board: &Board, to_move: Color) -> Setup {
+        Setup {
+            board: Board::new_from(board),
+            to_move,
+        }
+    }
+
+    // Runs one setup command
+    pub fn apply(&mut self, line: &str, params: &EvalParams) -> Result<SetupOutcome, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (command, args) = match words.split_first() {
+            Some((command, args)) => (command.to_lowercase(), args),
+            None => return Ok(SetupOutcome::Continue),
+        };
+        match command.as_str() {
+            "b" | "w" | "x" => {
+                let color = match command.as_str() {
+                    "b" => Color::Black,
+                    "w" => Color::White,
+                    _ => Color::Empty,
+                };
+                for p in parse_cells(&self.board, args)? {
+                    self.board.set_blocked(p, false);
+                    self.board.set_at(p, color);
+                }
+            }
+            "hole" => {
+                for p in parse_cells(&self.board, args)? {
+                    self.board.set_blocked(p, true);
+                }
+            }
+            "clear" => self.board = Board::new_sized(self.board.size()),
+            "start" => {
+                self.board = Board::new_start_sized(self.board.size());
+                self.to_move = Color::Black;
+            }
+            "side" => {
+                self.to_move = match args.first().map(|a| a.to_lowercase()).as_deref() {
+                    Some("b") | Some("black") => Color::Black,
+                    Some("w") | Some("white") => Color::White,
+                    _ => return Err(String::from("side must be b or w")),
+                }
+            }
+            "load" => {
+                // The side to move is optional here, unlike everywhere else
+                let arg = args.join(" ");
+                if arg.is_empty() {
+                    return Err(String::from("load what?"));
+                }
+                match Position::from_arg(&arg) {
+                    Ok(position) => {
+                        self.board = position.board;
+                        self.to_move = position.to_move;
+                    }
+                    Err(_) => self.board = arg.parse()?,
+                }
+            }
+            "save" => {
+                let position = Position::new(&self.board, self.to_move);
+                match args.first() {
+                    Some(path) => fs::write(path, position.to_string() + "\n")
+                        .map_err(|e| format!("cannot write {}: {}", path, e))?,
+                    None => println!("{}", position),
+                }
+            }
+            "eval" => {
+                println!("{}", explain_eval(&self.board, self.to_move, params));
+                println!("{}", explain_eval(&self.board, self.to_move.opposite(), params));
+            }
+            "done" => {
+                for warning in validate_position(&self.board, self.to_move)? {
+                    println!("Warning: {}", warning);
+                }
+                return Ok(SetupOutcome::Done);
+            }
+            "cancel" => return Ok(SetupOutcome::Cancel),
+            _ => return Err(format!("unknown setup command '{}'", command)),
+        }
+        Ok(SetupOutcome::Continue)
+    }
+}
+
+// Lets the user edit the position interactively. Returns the new position and the side
+// to move, or None if the user gave up.
+pub fn run_setup(board: &Board, to_move: Color, params: &EvalParams) -> Option<(Board, Color)> {
+    let mut setup = Setup::new(board, to_move);
+    println!("{}", SETUP_HELP);
+    loop {
+        setup.board.print();
+        println!("{:?} to move", setup.to_move);
+        print!("setup> ");
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).unwrap() == 0 {
+            return None;
+        }
+        match setup.apply(input.trim(), params) {
+            Ok(SetupOutcome::Continue) => {}
+            Ok(SetupOutcome::Done) => return Some((setup.board, setup.to_move)),
+            Ok(SetupOutcome::Cancel) => return None,
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+}
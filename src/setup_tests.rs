@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod test {
+
+    use crate::board::*;
+    use crate::color::*;
+    use crate::params::EvalParams;
+    use crate::setup::*;
+
+    #[test]
+    fn test_parse_cell() {
+        assert_eq!(Pos2D::parse("c4"), Some(Pos2D::new(2, 4)));
+        assert_eq!(Pos2D::parse("H0"), Some(Pos2D::new(7, 0)));
+        assert_eq!(Pos2D::parse("k0"), None);
+        // Rows past 7 exist only on a 10x10 board
+        assert_eq!(Pos2D::parse("h8"), Some(Pos2D::new(7, 8)));
+        let mut setup = Setup::new(&Board::new_start(), Color::Black);
+        assert!(setup.apply("b h8", &EvalParams::default()).is_err());
+        assert_eq!(Pos2D::parse("c"), None);
+    }
+
+    #[test]
+    fn test_setup_commands() {
+        let params = EvalParams::default();
+        let mut setup = Setup::new(&Board::new_start(), Color::Black);
+
+        assert_eq!(setup.apply("b c4 c2", &params), Ok(SetupOutcome::Continue));
+        assert_eq!(setup.board.get_at_c('c', 4), Color::Black);
+        assert_eq!(setup.board.get_at_c('c', 2), Color::Black);
+        setup.apply("x c2", &params).unwrap();
+        assert_eq!(setup.board.get_at_c('c', 2), Color::Empty);
+        setup.apply("side w", &params).unwrap();
+        assert_eq!(setup.to_move, Color::White);
+
+        let cells = setup.board.to_string();
+        setup.apply("clear", &params).unwrap();
+        assert_eq!(setup.board.num_occupied(), 0);
+        setup.apply(&format!("load {}", cells), &params).unwrap();
+        assert_eq!(setup.board.to_string(), cells);
+        assert_eq!(setup.to_move, Color::White);
+        setup.apply(&format!("load {} X", cells), &params).unwrap();
+        assert_eq!(setup.to_move, Color::Black);
+
+        assert!(setup.apply("b z9", &params).is_err());
+        assert!(setup.apply("side green", &params).is_err());
+        assert!(setup.apply("load XO", &params).is_err());
+        assert!(setup.apply("fly", &params).is_err());
+        assert_eq!(setup.apply("done", &params), Ok(SetupOutcome::Done));
+        assert_eq!(setup.apply("cancel", &params), Ok(SetupOutcome::Cancel));
+    }
+
+    #[test]
+    fn test_validate_position() {
+        let b = Board::new_start();
+        assert_eq!(validate_position(&b, Color::Black), Ok(vec![]));
+        assert_eq!(validate_position(&b, Color::White).unwrap().len(), 1);
+
+        let mut detached = Board::new_start();
+        detached.set_at_c('a', 0, Color::Black);
+        assert!(validate_position(&detached, Color::Black).is_err());
+
+        let mut hollow = Board::new_start();
+        hollow.set_at_c('d', 3, Color::Empty);
+        assert!(validate_position(&hollow, Color::Black).is_err());
+
+        let mut over = Board::new_start();
+        over.set_at_c('d', 4, Color::Black);
+        over.set_at_c('e', 3, Color::Black);
+        assert!(validate_position(&over, Color::White).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+#![allow(dead_code)]
+
+use crate::board::*;
+use crate::color::Color;
+use crate::position::Position;
+use crate::stat::Stat;
+
+// Score of a finished game from the point of view of `color`. As in tournament
+// scoring, cells left empty go to the winner, who in anti-reversi is the side
+// with fewer discs.
+pub fn final_score(board: &Board, color: Color) -> i32 {
+    let lead = board.disc_lead(color);
+    let empty = board.num_empty() as i32;
+    if lead > 0 {
+        lead + empty
+    } else if lead < 0 {
+        lead - empty
+    } else {
+        0
+    }
+}
+
+// Searches to the end of the game and returns the exact final score under perfect play,
+// together with the best move. The move is None if `color` has to pass.
+pub fn solve_exact(board: &Board, color: Color, stat: &mut Stat) -> (i32, Option<Pos2D>) {
+    let bound = board.num_cells() as i32 + 1;
+    solve_worker(board, color, -bound, bound, stat)
+}
+
+// Only the sign of the exact score: +1 for a win, 0 for a draw, -1 for a loss.
+// Cheaper than solve_exact because of the null window.
+pub fn solve_outcome(board: &Board, color: Color, stat: &mut Stat) -> i32 {
+    let (score, _) = solve_worker(board, color, -1, 1, stat);
+    score.signum()
+}
+
+fn solve_worker(
+    board: &Board,
+    color: Color,
+    alpha: i32,
+    beta: i32,
+    stat: &mut Stat) -> (i32, Option<Pos2D>) {
+
+    let opposite = color.opposite();
+    let mut children: Vec<_> = board
+        .get_available_moves_for(color)
+        .map(|mv| {
+            let mut child = Board::new_from(board);
+            child.place(mv, color);
+            (mv, child)
+        })
+        .collect();
+
+    if children.is_empty() {
+        if !board.has_any_moves(opposite) {
+            return (final_score(board, color), None);
+        }
+        let (score, _) = solve_worker(board, opposite, -beta, -alpha, stat);
+        return (-score, None);
+    }
+
+    // Fastest-first: replies that leave the opponent with the fewest options tend to
+    // cause cut-offs early. Not worth the extra move generation close to the end.
+    if children.len() > 1 && board.num_empty() > 6 {
+        children.sort_by_cached_key(|(_, child)| child.get_available_moves_for(opposite).count());
+    }
+
+    let mut alpha = alpha;
+    let mut value = i32::MIN + 1;
+    let mut best_move = None;
+    for (mv, child) in children {
+        stat.nodes_viewed += 1;
+        let (child_score, _) = solve_worker(&child, opposite, -beta, -alpha, stat);
+        let score = -child_score;
+        if score > value {
+            value = score;
+            best_move = Some(mv);
+        }
+        alpha = ::std::cmp::max(alpha, value);
+        if alpha >= beta {
+            break; // cut-off
+        }
+    }
+
+    (value, best_move)
+}
+
+pub struct SolveOptions {
+    pub position: Position,
+    pub variant: Variant,
+}
+
+pub fn run_solve(options: &SolveOptions) {
+    let mut stat = Stat::new();
+    let position = &options.position;
+    let mut board = Board::new_from(&position.board);
+    board.set_variant(options.variant);
+    let (score, best) = solve_exact(&board, position.to_move, &mut stat);
+    let elapsed = stat.start.elapsed();
+    println!(
+        "{:?} to move: {} with final score {:+}. Reviewed {} nodes. Elapsed {:?}.",
+        position.to_move,
+        best.map_or(String::from("pass"), |m| m.to_string()),
+        score,
+        stat.nodes_viewed,
+        elapsed
+    );
+}
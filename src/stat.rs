@@ -2,11 +2,27 @@
 
 use log::{error, info, set_max_level, trace, warn};
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use crate::board::Pos2D;
+use crate::table::Table;
+
+// Told the depth, score, expected line of play and nodes so far after every depth that an
+// iterative search finishes
+pub type Progress = Box<dyn FnMut(i32, i32, &[Pos2D], u64) + Send>;
+
 pub struct Stat {
-    pub nodes_viewed: u32,
+    pub nodes_viewed: u64,
     pub start: Instant,
+    // searches give up once this has passed or the flag is raised, and set `aborted`
+    pub deadline: Option<Instant>,
+    pub stop: Option<Arc<AtomicBool>>,
+    pub aborted: bool,
+    pub progress: Option<Progress>,
+    // positions searched before, e.g. while pondering, and where to store the new ones
+    pub table: Option<Arc<Table>>,
 }
 
 impl Stat {
@@ -14,6 +30,20 @@ impl Stat {
         Stat {
             nodes_viewed: 0,
             start: Instant::now(),
+            deadline: None,
+            stop: None,
+            aborted: false,
+            progress: None,
+            table: None,
+        }
+    }
+
+    // Whether the search has to stop now. The clock and the flag are only looked at every so many nodes.
+    pub fn should_stop(&mut self) -> bool {
+        if !self.aborted && self.nodes_viewed.is_multiple_of(256) {
+            self.aborted = self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                || self.stop.as_ref().is_some_and(|stop| stop.load(Ordering::Relaxed));
         }
+        self.aborted
     }
 }
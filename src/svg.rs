@@ -0,0 +1,288 @@
+#![allow(dead_code)]
+
+use crate::analysis::*;
+use crate::board::*;
+use crate::color::Color;
+use crate::params::EvalParams;
+use crate::position::Position;
+use crate::review::read_transcript;
+use crate::stat::Stat;
+use crate::transcript::*;
+
+// What goes on the board besides the discs
+#[derive(Default)]
+pub struct Diagram {
+    pub last_move: Option<Pos2D>,
+    // numbers written on the discs, as in a game record
+    pub move_numbers: Vec<(Pos2D, usize)>,
+    // marks on empty cells: a dot, or a score if there is one
+    pub overlay: Vec<(Pos2D, Option<i32>)>,
+}
+
+impl Diagram {
+    // Every move of `color`, marked with a dot
+    pub fn legal_moves(board: &Board, color: Color) -> Diagram {
+        Diagram {
+            overlay: board.get_available_moves_for(color).map(|p| (p, None)).collect(),
+            ..Diagram::default()
+        }
+    }
+
+    // Every move of `color` with its score from a search of `depth`, as `negamax` would count it
+    pub fn evaluated(board: &Board, color: Color, depth: i32, params: &EvalParams) -> Diagram {
+        let mut stat = Stat::new();
+        let overlay = board
+            .get_available_moves_for(color)
+            .map(|p| {
+                let mut child = Board::new_from(board);
+                child.place(p, color);
+                let (score, _) = negamax_worker(&child, color.opposite(), depth * 2 - 1, params, i32::MIN + 1, i32::MAX - 1, &mut stat);
+                (p, Some(-score))
+            })
+            .collect();
+        Diagram {
+            overlay,
+            ..Diagram::default()
+        }
+    }
+}
+
+// The board after the first `ply` moves of the game and the side to move then, with the
+// last of those moves marked and, if `numbers` is set, each of them numbered
+pub fn game_diagram(transcript: &Transcript, ply: usize, numbers: bool) -> Result<(Board, Color, Diagram), String> {
+    if ply > transcript.moves.len() {
+        return Err(format!("the game has only {} moves", transcript.moves.len()));
+    }
+    let mut board = transcript.start_board();
+    let mut to_move = transcript.first_mover();
+    let mut diagram = Diagram::default();
+    for (index, &(color, p)) in transcript.moves[..ply].iter().enumerate() {
+        if !board.can_place(p, color) {
+            return Err(format!("move {} ({} {}) is not legal", index + 1, color, p));
+        }
+        board.place(p, color);
+        to_move = color.opposite();
+        diagram.last_move = Some(p);
+        if numbers {
+            diagram.move_numbers.push((p, index + 1));
+        }
+    }
+    Ok((board, to_move, diagram))
+}
+
+pub struct SvgOptions {
+    // the width of a cell in pixels
+    pub cell_size: u32,
+    // letters and numbers around the board
+    pub coordinates: bool,
+    // number the rows from 1 as in standard notation, rather than from 0 as the engine's
+    // moves are; diagrams are for readers, so this is the default
+    pub standard_labels: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> SvgOptions {
+        SvgOptions {
+            cell_size: 40,
+            coordinates: true,
+            standard_labels: true,
+        }
+    }
+}
+
+// The board as a standalone SVG image
+pub fn board_svg(board: &Board, diagram: &Diagram, options: &SvgOptions) -> String {
+    let size = board.size() as u32;
+    let cell = options.cell_size;
+    let margin = if options.coordinates { cell / 2 } else { 0 };
+    let width = size * cell + 2 * margin;
+    let center = |i: usize| margin + i as u32 * cell + cell / 2;
+    let font = cell * 2 / 5;
+
+    let mut svg = String::new();
+    let mut put = |line: String| {
+        svg.push_str(&line);
+        svg.push('\n');
+    };
+    put(format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" viewBox=\"0 0 {0} {0}\" font-family=\"sans-serif\">",
+        width
+    ));
+    put(format!("<rect width=\"{0}\" height=\"{0}\" fill=\"white\"/>", width));
+    put(format!(
+        "<rect x=\"{0}\" y=\"{0}\" width=\"{1}\" height=\"{1}\" fill=\"#2e8b57\" stroke=\"black\" stroke-width=\"2\"/>",
+        margin,
+        size * cell
+    ));
+    for k in 1..size {
+        let at = margin + k * cell;
+        put(format!(
+            "<line x1=\"{0}\" y1=\"{1}\" x2=\"{0}\" y2=\"{2}\" stroke=\"black\"/>",
+            at,
+            margin,
+            margin + size * cell
+        ));
+        put(format!(
+            "<line x1=\"{1}\" y1=\"{0}\" x2=\"{2}\" y2=\"{0}\" stroke=\"black\"/>",
+            at,
+            margin,
+            margin + size * cell
+        ));
+    }
+    // The four dots that mark off the corner regions of the 8x8 board
+    if size == 8 {
+        for &(i, j) in &[(2, 2), (6, 2), (2, 6), (6, 6)] {
+            put(format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"black\"/>",
+                margin + i * cell,
+                margin + j * cell,
+                (cell / 16).max(2)
+            ));
+        }
+    }
+
+    if options.coordinates {
+        for k in 0..board.size() {
+            let column = (b'a' + k as u8) as char;
+            let row = if options.standard_labels { k + 1 } else { k };
+            put(format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                center(k),
+                margin / 2,
+                font,
+                column
+            ));
+            put(format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                margin / 2,
+                center(k),
+                font,
+                row
+            ));
+        }
+    }
+
+    for p in board.cells() {
+        let (x, y) = (center(p.i), center(p.j));
+        if board.is_blocked(p) {
+            put(format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{2}\" height=\"{2}\" fill=\"#555555\"/>",
+                x - cell / 2,
+                y - cell / 2,
+                cell
+            ));
+            continue;
+        }
+        let (fill, ink) = match board.get_at(p) {
+            Color::Black => ("black", "white"),
+            Color::White => ("white", "black"),
+            Color::Empty => continue,
+        };
+        put(format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke=\"black\"/>",
+            x,
+            y,
+            cell * 2 / 5,
+            fill
+        ));
+        let number = diagram.move_numbers.iter().find(|(q, _)| *q == p).map(|(_, n)| *n);
+        let last = diagram.last_move == Some(p);
+        match number {
+            Some(n) => put(format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\">{}</text>",
+                x,
+                y,
+                font,
+                if last { "red" } else { ink },
+                n
+            )),
+            None if last => put(format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"red\"/>", x, y, cell / 8)),
+            None => {}
+        }
+    }
+
+    for &(p, score) in &diagram.overlay {
+        let (x, y) = (center(p.i), center(p.j));
+        match score {
+            Some(score) => put(format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"yellow\">{}</text>",
+                x,
+                y,
+                font,
+                score
+            )),
+            None => put(format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"black\" fill-opacity=\"0.4\"/>",
+                x,
+                y,
+                cell / 8
+            )),
+        }
+    }
+    put(String::from("</svg>"));
+    svg
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Overlay {
+    None,
+    Legal,
+    // scores from a search of this depth
+    Eval(i32),
+}
+
+pub struct DiagramOptions {
+    // a position, or a transcript, either one given literally or in a file
+    pub input: String,
+    // how many moves of the transcript to play, all of them by default
+    pub ply: Option<usize>,
+    // number the moves of the transcript on the discs
+    pub numbers: bool,
+    pub overlay: Overlay,
+    // where to write the SVG, standard output by default
+    pub out: Option<String>,
+    pub svg: SvgOptions,
+}
+
+impl Default for DiagramOptions {
+    fn default() -> DiagramOptions {
+        DiagramOptions {
+            input: String::new(),
+            ply: None,
+            numbers: false,
+            overlay: Overlay::None,
+            out: None,
+            svg: SvgOptions::default(),
+        }
+    }
+}
+
+pub fn run_diagram(options: &DiagramOptions, params: &EvalParams) -> Result<(), String> {
+    let (board, to_move, mut diagram) = match Position::from_arg(&options.input) {
+        Ok(position) => {
+            if options.ply.is_some() || options.numbers {
+                return Err(String::from("--ply and --numbers need a transcript, not a position"));
+            }
+            (position.board, position.to_move, Diagram::default())
+        }
+        Err(_) => {
+            let transcript = read_transcript(&options.input, None)?;
+            let ply = options.ply.unwrap_or(transcript.moves.len());
+            game_diagram(&transcript, ply, options.numbers)?
+        }
+    };
+    diagram.overlay = match options.overlay {
+        Overlay::None => vec![],
+        Overlay::Legal => Diagram::legal_moves(&board, to_move).overlay,
+        Overlay::Eval(depth) => Diagram::evaluated(&board, to_move, depth, params).overlay,
+    };
+
+    let svg = board_svg(&board, &diagram, &options.svg);
+    match &options.out {
+        Some(path) => std::fs::write(path, svg).map_err(|e| format!("cannot write {}: {}", path, e)),
+        None => {
+            print!("{}", svg);
+            Ok(())
+        }
+    }
+}
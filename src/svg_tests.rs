@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod test {
+
+    use crate::board::*;
+    use crate::color::*;
+    use crate::params::EvalParams;
+    use crate::svg::*;
+    use crate::transcript::*;
+
+    fn count(svg: &str, element: &str) -> usize {
+        svg.matches(element).count()
+    }
+
+    #[test]
+    fn test_start_position() {
+        let board = Board::new_start();
+        let svg = board_svg(&board, &Diagram::default(), &SvgOptions::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"360\" height=\"360\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        // four discs and the four dots on the board
+        assert_eq!(count(&svg, "<circle"), 8);
+        assert_eq!(count(&svg, "fill=\"white\" stroke"), 2);
+        // seven lines each way, and a label for every row and column
+        assert_eq!(count(&svg, "<line"), 14);
+        assert_eq!(count(&svg, "<text"), 16);
+        assert!(svg.contains(">8</text>") && !svg.contains(">0</text>"));
+
+        let engine = SvgOptions { standard_labels: false, ..SvgOptions::default() };
+        let svg = board_svg(&board, &Diagram::default(), &engine);
+        assert!(svg.contains(">0</text>") && !svg.contains(">8</text>"));
+
+        let plain = SvgOptions { coordinates: false, ..SvgOptions::default() };
+        let svg = board_svg(&board, &Diagram::default(), &plain);
+        assert!(svg.contains("width=\"320\""));
+        assert_eq!(count(&svg, "<text"), 0);
+    }
+
+    #[test]
+    fn test_game_with_numbers() {
+        let transcript = Transcript::from_trace("bc4wc5bf3");
+        let (board, to_move, diagram) = game_diagram(&transcript, 2, true).unwrap();
+        assert_eq!(to_move, Color::Black);
+        assert_eq!(board.num_occupied(), 6);
+        assert_eq!(diagram.last_move, Some(Pos2D::new(2, 5)));
+        assert_eq!(diagram.move_numbers, vec![(Pos2D::new(2, 4), 1), (Pos2D::new(2, 5), 2)]);
+
+        let svg = board_svg(&board, &diagram, &SvgOptions::default());
+        assert!(svg.contains("fill=\"white\">1</text>"));
+        assert!(svg.contains("fill=\"red\">2</text>"));
+        assert!(game_diagram(&transcript, 4, false).is_err());
+    }
+
+    #[test]
+    fn test_overlays() {
+        let board = Board::new_start();
+        let legal = Diagram::legal_moves(&board, Color::Black);
+        assert_eq!(legal.overlay.len(), 4);
+        let svg = board_svg(&board, &legal, &SvgOptions::default());
+        assert_eq!(count(&svg, "fill-opacity"), 4);
+
+        let scored = Diagram::evaluated(&board, Color::Black, 1, &EvalParams::default());
+        assert_eq!(scored.overlay.len(), 4);
+        assert!(scored.overlay.iter().all(|(_, score)| score.is_some()));
+        let svg = board_svg(&board, &scored, &SvgOptions::default());
+        assert_eq!(count(&svg, "fill=\"yellow\""), 4);
+    }
+}
@@ -0,0 +1,193 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use crate::board::*;
+use crate::color::Color;
+use crate::position::Position;
+
+// The eight ways to turn or mirror the board onto itself
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipHorizontal,
+    FlipVertical,
+    FlipDiagonal,
+    FlipAntiDiagonal,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::FlipDiagonal,
+        Symmetry::FlipAntiDiagonal,
+    ];
+
+    // Where the cell ends up on a board with size x size cells
+    pub fn apply(self, p: Pos2D, size: usize) -> Pos2D {
+        let (i, j) = (p.i, p.j);
+        let last = size - 1;
+        match self {
+            Symmetry::Identity => Pos2D::new(i, j),
+            Symmetry::Rotate90 => Pos2D::new(last - j, i),
+            Symmetry::Rotate180 => Pos2D::new(last - i, last - j),
+            Symmetry::Rotate270 => Pos2D::new(j, last - i),
+            Symmetry::FlipHorizontal => Pos2D::new(last - i, j),
+            Symmetry::FlipVertical => Pos2D::new(i, last - j),
+            Symmetry::FlipDiagonal => Pos2D::new(j, i),
+            Symmetry::FlipAntiDiagonal => Pos2D::new(last - j, last - i),
+        }
+    }
+
+    // The symmetry that undoes this one
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => other,
+        }
+    }
+}
+
+impl Pos2D {
+    pub fn transform(self, symmetry: Symmetry, size: usize) -> Pos2D {
+        symmetry.apply(self, size)
+    }
+}
+
+impl Board {
+    pub fn transform(&self, symmetry: Symmetry) -> Board {
+        let mut board = Board::new_sized(self.size());
+        board.set_variant(self.variant());
+        for p in self.cells() {
+            let q = symmetry.apply(p, self.size());
+            board.set_at(q, self.get_at(p));
+            board.set_blocked(q, self.is_blocked(p));
+        }
+        board
+    }
+
+    // One bit per cell for each color, in the order of cells()
+    pub fn bits(&self) -> (u128, u128) {
+        let mut black = 0u128;
+        let mut white = 0u128;
+        for (index, p) in self.cells().enumerate() {
+            match self.get_at(p) {
+                Color::Black => black |= 1 << index,
+                Color::White => white |= 1 << index,
+                Color::Empty => {}
+            }
+        }
+        (black, white)
+    }
+
+    // The representative of the board's eight symmetric variants, and the symmetry
+    // that turns this board into it
+    pub fn canonical(&self) -> (Board, Symmetry) {
+        Symmetry::ALL
+            .iter()
+            .map(|&s| (self.transform(s), s))
+            .min_by_key(|(board, _)| (board.bits(), board.blocked_mask()))
+            .unwrap()
+    }
+}
+
+// Identifies a position up to symmetry, so that it can be looked up no matter which
+// of its eight variants came up on the board
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct PositionKey {
+    size: usize,
+    variant: Variant,
+    black: u128,
+    white: u128,
+    blocked: u128,
+    black_to_move: bool,
+}
+
+impl PositionKey {
+    // The key of the position, and the symmetry that maps its cells to the canonical ones
+    pub fn of(position: &Position) -> (PositionKey, Symmetry) {
+        let (canonical, symmetry) = position.board.canonical();
+        let (black, white) = canonical.bits();
+        let key = PositionKey {
+            size: canonical.size(),
+            variant: canonical.variant(),
+            black,
+            white,
+            blocked: canonical.blocked_mask(),
+            black_to_move: position.to_move == Color::Black,
+        };
+        (key, symmetry)
+    }
+}
+
+// A map from positions to values that treats symmetric positions as the same. Moves are
+// stored in canonical orientation and turned back to match the position that is asked about.
+pub struct PositionMap<V> {
+    entries: HashMap<PositionKey, V>,
+}
+
+impl<V> PositionMap<V> {
+    pub fn new() -> PositionMap<V> {
+        PositionMap { entries: HashMap::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        self.entries.contains_key(&PositionKey::of(position).0)
+    }
+
+    pub fn get(&self, position: &Position) -> Option<&V> {
+        self.entries.get(&PositionKey::of(position).0)
+    }
+
+    // Returns false, and leaves the map alone, if a symmetric variant is already there
+    pub fn insert_new(&mut self, position: &Position, value: V) -> bool {
+        let (key, _) = PositionKey::of(position);
+        if self.entries.contains_key(&key) {
+            return false;
+        }
+        self.entries.insert(key, value);
+        true
+    }
+}
+
+impl<V> Default for PositionMap<V> {
+    fn default() -> PositionMap<V> {
+        PositionMap::new()
+    }
+}
+
+impl PositionMap<Vec<(Pos2D, i32)>> {
+    // Records a scored move for the position
+    pub fn add_move(&mut self, position: &Position, mv: Pos2D, score: i32) {
+        let (key, symmetry) = PositionKey::of(position);
+        let mv = mv.transform(symmetry, position.board.size());
+        self.entries.entry(key).or_default().push((mv, score));
+    }
+
+    // The scored moves recorded for the position or any of its variants, in its own orientation
+    pub fn moves(&self, position: &Position) -> Vec<(Pos2D, i32)> {
+        let (key, symmetry) = PositionKey::of(position);
+        let (inverse, size) = (symmetry.inverse(), position.board.size());
+        self.entries
+            .get(&key)
+            .map(|moves| moves.iter().map(|&(mv, score)| (mv.transform(inverse, size), score)).collect())
+            .unwrap_or_default()
+    }
+}
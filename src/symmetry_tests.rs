@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod test {
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::board::*;
+    use crate::color::*;
+    use crate::perft::*;
+    use crate::position::*;
+    use crate::selfplay::*;
+    use crate::symmetry::*;
+
+    fn get_midgame_position() -> Position {
+        let mut rng = StdRng::seed_from_u64(34);
+        let opening = random_opening(20, &mut rng);
+        let mut b = Board::new_start();
+        let color = b.replay_transcript(&opening).opposite();
+        Position::new(&b, color)
+    }
+
+    #[test]
+    fn test_inverse_undoes_transform() {
+        let p = get_midgame_position();
+        for &s in &Symmetry::ALL {
+            assert_eq!(p.board.transform(s).transform(s.inverse()).to_string(), p.board.to_string());
+            assert_eq!(Pos2D::new(1, 6).transform(s, 8).transform(s.inverse(), 8), Pos2D::new(1, 6));
+        }
+    }
+
+    #[test]
+    fn test_transform_preserves_moves() {
+        let p = get_midgame_position();
+        let moves = p.board.get_available_moves_for(p.to_move).collect::<Vec<_>>();
+        for &s in &Symmetry::ALL {
+            let b = p.board.transform(s);
+            let mut transformed = b.get_available_moves_for(p.to_move).collect::<Vec<_>>();
+            let mut expected = moves.iter().map(|m| m.transform(s, 8)).collect::<Vec<_>>();
+            transformed.sort_by_key(|m| m.j * 8 + m.i);
+            expected.sort_by_key(|m| m.j * 8 + m.i);
+            assert_eq!(transformed, expected);
+            assert_eq!(perft(&b, p.to_move, 3), perft(&p.board, p.to_move, 3));
+        }
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let p = get_midgame_position();
+        let (key, _) = PositionKey::of(&p);
+        for &s in &Symmetry::ALL {
+            let variant = Position::new(&p.board.transform(s), p.to_move);
+            let (variant_key, symmetry) = PositionKey::of(&variant);
+            assert_eq!(variant_key, key);
+            assert_eq!(variant.board.transform(symmetry).to_string(), p.board.canonical().0.to_string());
+        }
+        let other_side = Position::new(&p.board, p.to_move.opposite());
+        assert_ne!(PositionKey::of(&other_side).0, key);
+        let mut anti = Position::new(&p.board.transform(Symmetry::Rotate90), p.to_move);
+        anti.board.set_variant(Variant::Anti);
+        assert_ne!(PositionKey::of(&anti).0, key);
+        assert_eq!(anti.board.canonical().0.variant(), Variant::Anti);
+    }
+
+    #[test]
+    fn test_position_map() {
+        let p = get_midgame_position();
+        let mv = p.board.get_available_moves_for(p.to_move).next().unwrap();
+        let mut book = PositionMap::new();
+        book.add_move(&p, mv, 12);
+
+        for &s in &Symmetry::ALL {
+            let variant = Position::new(&p.board.transform(s), p.to_move);
+            let moves = book.moves(&variant);
+            assert_eq!(moves, vec![(mv.transform(s, 8), 12)]);
+            assert!(variant.board.can_place(moves[0].0, p.to_move));
+        }
+
+        let mut seen = PositionMap::new();
+        assert!(seen.insert_new(&Position::start(), ()));
+        let mirrored = Position::new(&Board::new_start().transform(Symmetry::Rotate90), Color::Black);
+        assert!(!seen.insert_new(&mirrored, ()));
+        assert_eq!(seen.len(), 1);
+    }
+}
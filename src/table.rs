@@ -0,0 +1,74 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::board::*;
+use crate::color::Color;
+
+// How a stored score relates to the real one: the search that found it may have been cut off
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bound {
+    Exact,
+    // the score is at least this
+    Lower,
+    // the score is at most this
+    Upper,
+}
+
+// What a finished search found out about a position, `plies` deep
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Entry {
+    pub plies: i32,
+    pub score: i32,
+    pub bound: Bound,
+    pub best: Option<Pos2D>,
+}
+
+// Positions the search has already been through, shared by searches on other threads,
+// e.g. pondering and the engine's own search. Only meant for boards of one game, so the
+// size, variant and blocked cells are not part of the key.
+#[derive(Default)]
+pub struct Table {
+    entries: Mutex<HashMap<(u128, u128, bool), Entry>>,
+}
+
+// Entries the table keeps at most; once full, only positions already in it are updated
+const CAPACITY: usize = 1 << 20;
+
+fn key(board: &Board, color: Color) -> (u128, u128, bool) {
+    let (black, white) = board.bits();
+    (black, white, color == Color::Black)
+}
+
+impl Table {
+    pub fn probe(&self, board: &Board, color: Color) -> Option<Entry> {
+        self.entries.lock().unwrap().get(&key(board, color)).copied()
+    }
+
+    // Keeps the entry unless a deeper one is stored for the position already
+    pub fn store(&self, board: &Board, color: Color, entry: Entry) {
+        let mut entries = self.entries.lock().unwrap();
+        let full = entries.len() >= CAPACITY;
+        match entries.get_mut(&key(board, color)) {
+            Some(old) if old.plies <= entry.plies => *old = entry,
+            Some(_) => {}
+            None if !full => {
+                entries.insert(key(board, color), entry);
+            }
+            None => {}
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
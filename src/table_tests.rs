@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::analysis::*;
+    use crate::board::*;
+    use crate::color::*;
+    use crate::params::EvalParams;
+    use crate::stat::Stat;
+    use crate::table::*;
+
+    #[test]
+    fn test_store_keeps_the_deeper_entry() {
+        let table = Table::default();
+        let board = Board::new_start();
+        let entry = |plies| Entry { plies, score: plies, bound: Bound::Exact, best: None };
+        table.store(&board, Color::Black, entry(4));
+        table.store(&board, Color::Black, entry(2));
+        assert_eq!(table.probe(&board, Color::Black), Some(entry(4)));
+        assert_eq!(table.probe(&board, Color::White), None);
+        table.store(&board, Color::Black, entry(6));
+        assert_eq!(table.probe(&board, Color::Black), Some(entry(6)));
+        table.clear();
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn test_search_with_table() {
+        let params = EvalParams::default();
+        let mut board = Board::new_start();
+        let first_move = board.get_available_moves_for(Color::Black).next().unwrap();
+        board.place(first_move, Color::Black);
+        let (_, score) = negamax(&board, Color::White, 3, &params, &mut Stat::new()).unwrap();
+
+        let table = Arc::new(Table::default());
+        let mut first = Stat::new();
+        first.table = Some(table.clone());
+        let (_, first_score) = negamax(&board, Color::White, 3, &params, &mut first).unwrap();
+        assert_eq!(first_score, score);
+        assert!(!table.is_empty());
+
+        // searching again, the table settles the position at once
+        let mut again = Stat::new();
+        again.table = Some(table.clone());
+        let (_, again_score) = negamax(&board, Color::White, 3, &params, &mut again).unwrap();
+        assert_eq!(again_score, score);
+        assert!(again.nodes_viewed < first.nodes_viewed);
+    }
+}
@@ -4,58 +4,113 @@ use log::{error, info, set_max_level, trace, warn};
 use std::fmt;
 use crate::board::*;
 use crate::color::*;
+use crate::position::Position;
 
+// The moves of a game, written as e.g. "bc4wc3". Games with other rules or another
+// start than the usual ones begin with headers such as "variant=anti" or "start=<position>",
+// separated from the moves and each other by spaces.
 pub struct Transcript {
-    pub moves: Vec<(Color, Pos2D)>
+    pub moves: Vec<(Color, Pos2D)>,
+    pub variant: Variant,
+    // None for the standard starting position
+    pub start: Option<Position>,
 }
 
 impl Transcript {
     pub fn new() -> Transcript {
-        Transcript { moves: vec![] }
+        Transcript { moves: vec![], variant: Variant::Normal, start: None }
+    }
+
+    // A transcript for a game from `start`, which is only recorded if it is not the usual one
+    pub fn starting_at(start: &Position) -> Transcript {
+        let mut transcript = Transcript::new();
+        if start.to_string() != Position::start().to_string() {
+            transcript.start = Some(start.clone());
+        }
+        transcript
+    }
+
+    // The board the game starts on, with the rules it is played by
+    pub fn start_board(&self) -> Board {
+        let mut board = match &self.start {
+            Some(start) => Board::new_from(&start.board),
+            None => Board::new_start(),
+        };
+        board.set_variant(self.variant);
+        board
+    }
+
+    pub fn first_mover(&self) -> Color {
+        self.start.as_ref().map_or(Color::Black, |start| start.to_move)
     }
 
     pub fn from_trace(trace: &str) -> Transcript {
+        Transcript::parse(trace).unwrap()
+    }
+
+    // Like from_trace, but reports malformed input instead of panicking
+    pub fn parse(trace: &str) -> Result<Transcript, String> {
         let mut t = Transcript::new();
-        let bytes = trace.as_bytes();
-        let mut index = 0;
-        loop {
-            let mover = if bytes[index] == 'b' as u8 { Color::Black } else { Color::White };
-            index += 1;
-            let chi = bytes[index];
-            index += 1;
-            let chj = bytes[index];
-            index += 1;
-
-            let i = (chi as usize) - 97;
-            let j = (chj as usize) - 48;
-            trace!("Mapped {},{} -> {},{}", chi, chj, i, j);
-
-            t.add(Pos2D::new(i, j), mover);
-            if index == bytes.len() {
-                break;
+        let mut moves = String::new();
+        for word in trace.split_whitespace() {
+            match word.split_once('=') {
+                Some(("variant", value)) => t.variant = value.parse()?,
+                Some(("start", value)) => t.start = Some(value.parse()?),
+                Some((key, _)) => return Err(format!("unknown header '{}'", key)),
+                None => moves.push_str(word),
             }
         }
-        t
+        let bytes = moves.as_bytes();
+        if !bytes.len().is_multiple_of(3) {
+            return Err(format!("transcript length {} is not a multiple of 3", bytes.len()));
+        }
+        for (index, mv) in bytes.chunks(3).enumerate() {
+            let (mover, p) = parse_move(mv).map_err(|e| format!("move {} {}", index + 1, e))?;
+            t.add(p, mover);
+        }
+        Ok(t)
     }
 
     pub fn add(&mut self, position: Pos2D, mover: Color) {
         self.moves.push((mover, position))
     }
+}
+
+// One move as transcripts write it, e.g. "bc4"
+pub fn format_move(mover: Color, p: Pos2D) -> String {
+    format!("{}{}{}", if mover == Color::Black { 'b' } else { 'w' }, ((p.i as u8) + 97) as char, p.j)
+}
 
-    pub fn back(&mut self) -> Color {
-        let _ = self.moves.pop();
-        let last = self.moves.pop();
-        last.unwrap().0
+// The three characters of a move, as format_move writes them
+pub fn parse_move(mv: &[u8]) -> Result<(Color, Pos2D), String> {
+    let mover = match mv[0] {
+        b'b' => Color::Black,
+        b'w' => Color::White,
+        _ => return Err(String::from("does not start with 'b' or 'w'")),
+    };
+    let (chi, chj) = (mv[1], mv[2]);
+    if !(b'a'..=b'j').contains(&chi) || !chj.is_ascii_digit() {
+        return Err(String::from("has bad coordinates"));
     }
+
+    let i = (chi as usize) - 97;
+    let j = (chj as usize) - 48;
+    trace!("Mapped {},{} -> {},{}", chi, chj, i, j);
+    Ok((mover, Pos2D::new(i, j)))
 }
 
 impl fmt::Display for Transcript {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut output = String::from("");
-        for p in self.moves.clone() {
-            output.push_str(if p.0 == Color::Black { "b" } else { "w"} );
-            output.push(((p.1.i as u8) + 97) as char);
-            output.push_str(&p.1.j.to_string());
+        if self.variant != Variant::Normal {
+            output.push_str(&format!("variant={} ", self.variant));
+        }
+        if let Some(start) = &self.start {
+            let side = if start.to_move == Color::Black { 'X' } else { 'O' };
+            output.push_str(&format!("start={}{} ", start.board, side));
+        }
+        for &(mover, p) in &self.moves {
+            output.push_str(&format_move(mover, p));
         }
         write!(f, "{}", output)
     }
@@ -0,0 +1,284 @@
+#![allow(dead_code)]
+
+use log::{trace, warn};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::analysis::*;
+use crate::board::*;
+use crate::color::Color;
+use crate::openings::Openings;
+use crate::params::*;
+use crate::position::Position;
+use crate::review::read_transcripts;
+use crate::selfplay::*;
+use crate::solve::*;
+use crate::stat::Stat;
+use crate::symmetry::PositionMap;
+use crate::transcript::*;
+
+pub struct TuneOptions {
+    // number of self-play games to take positions from
+    pub games: usize,
+    // file with one transcript per line, used in addition to the self-play games
+    pub transcripts: Option<String>,
+    // positions with this many empty cells or fewer are labelled by exact search
+    // instead of by the outcome of the game they come from
+    pub exact_empties: i32,
+    // search depth for self-play and for the verification match
+    pub depth: i32,
+    // random moves at the start of every self-play and match game, for variety
+    pub random_plies: usize,
+    // start self-play and match games from the even openings instead of random moves
+    pub even_openings: bool,
+    // pairs of games in the verification match
+    pub match_pairs: usize,
+    pub seed: Option<u64>,
+    pub out: String,
+}
+
+impl Default for TuneOptions {
+    fn default() -> TuneOptions {
+        TuneOptions {
+            games: 200,
+            transcripts: None,
+            exact_empties: 0,
+            depth: 1,
+            random_plies: 8,
+            even_openings: false,
+            match_pairs: 20,
+            seed: None,
+            out: String::from("tuned.toml"),
+        }
+    }
+}
+
+// A position reduced to what eval sees of it, from the side to move's point of view,
+// and the result it led to: 1 for a win, 0.5 for a draw, 0 for a loss
+pub struct Sample {
+    pub midgame: bool,
+    pub features: [i32; 7],
+    pub result: f64,
+}
+
+impl Sample {
+    fn eval(&self, weights: &[[i32; 7]; 2]) -> i32 {
+        let weights = &weights[self.midgame as usize];
+        self.features.iter().zip(weights.iter()).map(|(f, w)| f * w).sum()
+    }
+}
+
+// Turns every position of a finished game into a sample. Positions that eval scores by
+// disc count alone say nothing about the weights and are skipped.
+pub fn collect_samples(
+    transcript: &Transcript,
+    params: &EvalParams,
+    exact_empties: i32,
+    stat: &mut Stat) -> Vec<Sample> {
+    labelled_positions(transcript, params, exact_empties, stat)
+        .into_iter()
+        .map(|(_, sample)| sample)
+        .collect()
+}
+
+fn labelled_positions(
+    transcript: &Transcript,
+    params: &EvalParams,
+    exact_empties: i32,
+    stat: &mut Stat) -> Vec<(Position, Sample)> {
+
+    let mut final_board = transcript.start_board();
+    final_board.replay_transcript(transcript);
+    if final_board.has_any_moves(Color::Black) || final_board.has_any_moves(Color::White) {
+        warn!("Skipping unfinished game {}", transcript);
+        return vec![];
+    }
+    let black_outcome = final_score(&final_board, Color::Black).signum();
+
+    let mut samples = vec![];
+    let mut board = transcript.start_board();
+    for &(color, pos) in &transcript.moves {
+        let occupied = game_phase(&board);
+        if occupied < params.count_from {
+            let outcome = if board.num_empty() as i32 <= exact_empties {
+                solve_outcome(&board, color, stat)
+            } else if color == Color::Black {
+                black_outcome
+            } else {
+                -black_outcome
+            };
+            let sample = Sample {
+                midgame: occupied >= params.midgame_from,
+                features: eval_features(&board, color),
+                result: (outcome + 1) as f64 / 2.0,
+            };
+            samples.push((Position::new(&board, color), sample));
+        }
+        board.place(pos, color);
+    }
+    samples
+}
+
+// Samples from many games. A position that comes up again, in any orientation, is kept
+// once with the average of its results, so the common openings don't outweigh the rest.
+#[derive(Default)]
+pub struct SampleSet {
+    index: PositionMap<usize>,
+    pub samples: Vec<Sample>,
+    counts: Vec<u32>,
+}
+
+impl SampleSet {
+    pub fn add_game(&mut self, transcript: &Transcript, params: &EvalParams, exact_empties: i32, stat: &mut Stat) {
+        for (position, sample) in labelled_positions(transcript, params, exact_empties, stat) {
+            match self.index.get(&position) {
+                Some(&i) => {
+                    let count = self.counts[i] as f64;
+                    self.samples[i].result = (self.samples[i].result * count + sample.result) / (count + 1.0);
+                    self.counts[i] += 1;
+                }
+                None => {
+                    self.index.insert_new(&position, self.samples.len());
+                    self.samples.push(sample);
+                    self.counts.push(1);
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+// Mean squared difference between the results and the win probabilities predicted
+// from eval, mapped through a sigmoid with the given scale
+pub fn mean_error(samples: &[Sample], weights: &[[i32; 7]; 2], scale: f64) -> f64 {
+    let total: f64 = samples
+        .iter()
+        .map(|s| {
+            let predicted = sigmoid(scale * s.eval(weights) as f64);
+            (s.result - predicted) * (s.result - predicted)
+        })
+        .sum();
+    total / samples.len().max(1) as f64
+}
+
+// Finds the sigmoid scale that fits the current weights best, by ternary search on its
+// logarithm. Fixed for the rest of the tuning so that the weights cannot just grow.
+pub fn fit_scale(samples: &[Sample], weights: &[[i32; 7]; 2]) -> f64 {
+    let (mut lo, mut hi) = ((1e-5f64).ln(), (1.0f64).ln());
+    for _ in 0..60 {
+        let m1 = lo + (hi - lo) / 3.0;
+        let m2 = hi - (hi - lo) / 3.0;
+        if mean_error(samples, weights, m1.exp()) < mean_error(samples, weights, m2.exp()) {
+            hi = m2;
+        } else {
+            lo = m1;
+        }
+    }
+    ((lo + hi) / 2.0).exp()
+}
+
+// Upper bound on the passes over all weights. On small or one-sided sets of positions the
+// error can keep shrinking as weights grow without bound.
+const MAX_PASSES: usize = 1000;
+
+// Texel-style local search: nudge one weight at a time while that lowers the error,
+// halving the step whenever no nudge helps
+pub fn tune_weights(samples: &[Sample], params: &EvalParams, scale: f64) -> EvalParams {
+    let mut weights = [params.opening.to_array(), params.midgame.to_array()];
+    let mut best = mean_error(samples, &weights, scale);
+    let mut step = 16;
+    let mut passes = 0;
+    while step > 0 && passes < MAX_PASSES {
+        passes += 1;
+        let mut improved = false;
+        for phase in 0..2 {
+            for term in 0..7 {
+                for &delta in &[step, -step] {
+                    weights[phase][term] += delta;
+                    let error = mean_error(samples, &weights, scale);
+                    if error < best {
+                        best = error;
+                        improved = true;
+                        break;
+                    }
+                    weights[phase][term] -= delta;
+                }
+            }
+        }
+        if !improved {
+            step /= 2;
+        }
+        trace!("step {} error {:.6}", step, best);
+    }
+
+    EvalParams {
+        opening: Weights::from_array(weights[0]),
+        midgame: Weights::from_array(weights[1]),
+        ..params.clone()
+    }
+}
+
+pub fn run_tune(options: &TuneOptions, params: &EvalParams) -> Result<(), String> {
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let openings = if options.even_openings { Openings::Even } else { Openings::Random(options.random_plies) };
+    let mut stat = Stat::new();
+    let mut samples = SampleSet::default();
+
+    if let Some(path) = &options.transcripts {
+        for transcript in read_transcripts(path)? {
+            samples.add_game(&transcript, params, options.exact_empties, &mut stat);
+        }
+        println!("Collected {} distinct positions from {}", samples.len(), path);
+    }
+
+    for game in 0..options.games {
+        let opening = openings.pick(&mut rng);
+        let (transcript, _) = play_out(&opening, params, params, options.depth)?;
+        samples.add_game(&transcript, params, options.exact_empties, &mut stat);
+        if (game + 1) % 50 == 0 {
+            println!("Played {} self-play games", game + 1);
+        }
+    }
+    println!("Collected {} distinct positions in total", samples.len());
+    if samples.is_empty() {
+        return Err(String::from("no positions to tune on"));
+    }
+    let samples = samples.samples;
+
+    let before = [params.opening.to_array(), params.midgame.to_array()];
+    let scale = fit_scale(&samples, &before);
+    let error_before = mean_error(&samples, &before, scale);
+    let tuned = tune_weights(&samples, params, scale);
+    let after = [tuned.opening.to_array(), tuned.midgame.to_array()];
+    let error_after = mean_error(&samples, &after, scale);
+    println!("Sigmoid scale {:.6}. Error before {:.6}, after {:.6}", scale, error_before, error_after);
+    println!("Opening weights: {:?}", tuned.opening);
+    println!("Midgame weights: {:?}", tuned.midgame);
+
+    tuned.save(&options.out)?;
+    println!("Wrote {}", options.out);
+
+    let result = play_match(&tuned, params, options.match_pairs, &openings, options.depth, &mut rng)?;
+    println!(
+        "Tuned vs. original: {} wins, {} losses, {} draws ({:.1}%)",
+        result.wins,
+        result.losses,
+        result.draws,
+        result.score() * 100.0
+    );
+    Ok(())
+}
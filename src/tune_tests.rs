@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod test {
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::analysis::*;
+    use crate::board::*;
+    use crate::color::*;
+    use crate::options::Options;
+    use crate::params::*;
+    use crate::selfplay::*;
+    use crate::solve::*;
+    use crate::stat::Stat;
+    use crate::transcript::*;
+    use crate::tune::*;
+
+    #[test]
+    fn test_features_match_eval() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let opening = random_opening(30, &mut rng);
+        let mut board = Board::new_start();
+        let mut params = EvalParams::default();
+        params.set("frontier=-4").unwrap();
+        params.set("c_square=-25").unwrap();
+        params.set("parity=9").unwrap();
+        let weights = params.midgame.to_array();
+        for &(color, pos) in &opening.moves {
+            let features = eval_features(&board, color);
+            let dot: i32 = features.iter().zip(weights.iter()).map(|(f, w)| f * w).sum();
+            if board.num_occupied() as i32 >= params.midgame_from {
+                assert_eq!(dot, eval(&board, color, &params));
+            }
+            board.place(pos, color);
+        }
+    }
+
+    #[test]
+    fn test_solve_exact() {
+        // White takes the last cell and flips the whole row and column
+        let mut b = Board::new();
+        for i in 0..8 {
+            for j in 0..8 {
+                b.set_at(Pos2D::new(i, j), if i + j < 7 { Color::White } else { Color::Black });
+            }
+        }
+        b.set_at(Pos2D::new(0, 0), Color::Empty);
+        b.set_at(Pos2D::new(1, 0), Color::Black);
+        let mut stat = Stat::new();
+        let (score, pos) = solve_exact(&b, Color::White, &mut stat);
+        assert_eq!(pos, Some(Pos2D::new(0, 0)));
+        let mut after = Board::new_from(&b);
+        after.place(Pos2D::new(0, 0), Color::White);
+        assert_eq!(score, final_score(&after, Color::White));
+        assert_eq!(solve_outcome(&b, Color::White, &mut stat), score.signum());
+    }
+
+    #[test]
+    fn test_collect_samples() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let params = EvalParams::default();
+        let opening = random_opening(10, &mut rng);
+        let (transcript, board) = play_out(&opening, &params, &params, 1).unwrap();
+        let black = final_score(&board, Color::Black).signum();
+
+        let samples = collect_samples(&transcript, &params, 0, &mut Stat::new());
+        assert!(!samples.is_empty());
+        assert!(samples.len() <= transcript.moves.len());
+        let expected = (black + 1) as f64 / 2.0;
+        assert_eq!(samples[0].result, expected);
+        assert!(samples.iter().all(|s| s.result == expected || s.result == 1.0 - expected));
+
+        // Unfinished games carry no result
+        let unfinished = Transcript::from_trace("bc4wc3");
+        assert!(collect_samples(&unfinished, &params, 0, &mut Stat::new()).is_empty());
+    }
+
+    #[test]
+    fn test_sample_set_merges_repeated_positions() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let params = EvalParams::default();
+        let opening = random_opening(10, &mut rng);
+        let (transcript, _) = play_out(&opening, &params, &params, 1).unwrap();
+        let single = collect_samples(&transcript, &params, 0, &mut Stat::new());
+
+        let mut set = SampleSet::default();
+        set.add_game(&transcript, &params, 0, &mut Stat::new());
+        set.add_game(&transcript, &params, 0, &mut Stat::new());
+        assert_eq!(set.len(), single.len());
+        assert_eq!(set.samples[0].result, single[0].result);
+
+        // All four first moves lead to symmetric positions, so the games overlap
+        let mut set = SampleSet::default();
+        set.add_game(&transcript, &params, 0, &mut Stat::new());
+        let len = set.len();
+        let (flipped, _) = play_out(&Transcript::from_trace("bd5"), &params, &params, 1).unwrap();
+        let flipped_samples = collect_samples(&flipped, &params, 0, &mut Stat::new());
+        set.add_game(&flipped, &params, 0, &mut Stat::new());
+        assert!(set.len() <= len + flipped_samples.len() - 2);
+    }
+
+    #[test]
+    fn test_tuning_does_not_increase_error() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let params = EvalParams::default();
+        let mut samples = vec![];
+        for _ in 0..4 {
+            let opening = random_opening(8, &mut rng);
+            let (transcript, _) = play_out(&opening, &params, &params, 1).unwrap();
+            samples.extend(collect_samples(&transcript, &params, 0, &mut Stat::new()));
+        }
+        let before = [params.opening.to_array(), params.midgame.to_array()];
+        let scale = fit_scale(&samples, &before);
+        let tuned = tune_weights(&samples, &params, scale);
+        let after = [tuned.opening.to_array(), tuned.midgame.to_array()];
+        assert!(mean_error(&samples, &after, scale) <= mean_error(&samples, &before, scale));
+        assert_eq!(tuned.count_from, params.count_from);
+    }
+
+    #[test]
+    fn test_depth_must_be_positive() {
+        assert!(play_out(&Transcript::new(), &EvalParams::default(), &EvalParams::default(), 0).is_err());
+        let args: Vec<String> = ["tune", "--depth", "0"].iter().map(|s| s.to_string()).collect();
+        assert!(Options::from_args(&args).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+#![allow(dead_code)]
+
+use std::fmt;
+
+use crate::analysis::*;
+use crate::board::*;
+use crate::color::Color;
+use crate::explain::*;
+use crate::params::EvalParams;
+use crate::review::*;
+use crate::stat::Stat;
+
+// A term has to be worth this much more in the better line before it counts as a reason
+const MIN_REASON: i32 = 5;
+// Parity only matters with this few empty cells left
+const PARITY_EMPTIES: usize = 20;
+
+// How a move turns out: its score, the reply the search expects, and the evaluation
+// of the position after that reply, from the mover's point of view
+pub struct MoveLine {
+    pub mv: Pos2D,
+    pub score: i32,
+    pub reply: Option<Pos2D>,
+    pub after: EvalBreakdown,
+    empties: usize,
+}
+
+impl MoveLine {
+    fn new(board: &Board, color: Color, mv: Pos2D, depth: i32, params: &EvalParams, stat: &mut Stat) -> MoveLine {
+        let mut child = Board::new_from(board);
+        child.place(mv, color);
+        stat.nodes_viewed += 1;
+        // one ply less than a search from `board`, as in the review
+        let (score, line) = principal_variation(&child, color.opposite(), depth * 2 - 1, params, stat);
+        let reply = line.first().copied();
+        if let Some(reply) = reply {
+            child.place(reply, color.opposite());
+        }
+        MoveLine {
+            mv,
+            score: -score,
+            reply,
+            after: explain_eval(&child, color, params),
+            empties: child.num_empty(),
+        }
+    }
+}
+
+// Why `worse` is worse than `better` for the side that moves, in plain words, the
+// biggest difference first. Terms the evaluation gives no weight don't count, except
+// for a wipe-out and for parity near the end.
+pub fn reasons(worse: &MoveLine, better: &MoveLine) -> Vec<String> {
+    let opponent = format!("{:?}", worse.after.color.opposite());
+    let mut found: Vec<(i32, String)> = vec![];
+    for (w, b) in worse.after.terms.iter().zip(&better.after.terms) {
+        let difference = b.score() - w.score();
+        let wiped_out = w.term == Term::Mobility && w.own == 0 && b.own > 0;
+        let parity_lost = w.term == Term::Parity && w.own < b.own && worse.empties <= PARITY_EMPTIES;
+        if difference < MIN_REASON && !wiped_out && !parity_lost {
+            continue;
+        }
+        let reason = match w.term {
+            Term::Mobility if wiped_out => format!("lets {} wipe out your moves, so you have to pass", opponent),
+            Term::Mobility => format!(
+                "leaves you {} moves against {} for {}, where {} leaves {} against {}",
+                w.own, w.opponent, opponent, better.mv, b.own, b.opponent
+            ),
+            Term::Corners if w.opponent > b.opponent => format!("gives up a corner to {}", opponent),
+            Term::Corners => String::from("misses the chance to take a corner"),
+            Term::XSquares if w.own > b.own => String::from("plays an X-square, diagonally next to an empty corner"),
+            Term::CSquares if w.own > b.own => String::from("plays a C-square, on the edge next to an empty corner"),
+            Term::EdgeRuns if w.opponent > b.opponent => format!("gives {} a run of discs along an edge from a corner", opponent),
+            Term::EdgeRuns => String::from("gives up discs along an edge from your corner"),
+            Term::Frontier => String::from("leaves more of your discs next to empty squares, for the opponent to turn"),
+            Term::Parity => format!("loses parity: {} gets the last move", opponent),
+            Term::Discs => format!("ends up {} discs worse off", difference.abs()),
+            term => format!("is worse for {}", term.name()),
+        };
+        found.push((difference, reason));
+    }
+    found.sort_by_key(|(difference, _)| -difference);
+    found.into_iter().map(|(_, reason)| reason).collect()
+}
+
+// A warning about a move that loses too much against the best one
+pub struct Advice<'a> {
+    pub verdict: Verdict,
+    pub loss: i32,
+    pub played: &'a MoveLine,
+    pub best: &'a MoveLine,
+    pub reasons: Vec<String>,
+}
+
+impl fmt::Display for Advice<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let opponent = format!("{:?}", self.played.after.color.opposite());
+        writeln!(f, "Tutor: {} looks like a {}, {} worse than {}.", self.played.mv, self.verdict, self.loss, self.best.mv)?;
+        match self.played.reply {
+            Some(reply) => write!(f, "  After {} and {}'s answer {}, it", self.played.mv, opponent, reply)?,
+            None => write!(f, "  {} has no answer to {}, but it", opponent, self.played.mv)?,
+        }
+        match self.reasons.as_slice() {
+            [] => writeln!(f, " scores {} against {} for {}.", self.played.score, self.best.score, self.best.mv)?,
+            reasons => writeln!(f, " {}.", reasons.join(", and it "))?,
+        }
+        write!(f, "  Better is {} (score {} against {}).", self.best.mv, self.best.score, self.played.score)
+    }
+}
+
+// Every move of the side to move looked at in advance, to explain the hint and check
+// the move the human picks
+pub struct Tutor {
+    // the best first
+    pub lines: Vec<MoveLine>,
+}
+
+impl Tutor {
+    pub fn new(board: &Board, color: Color, depth: i32, params: &EvalParams) -> Tutor {
+        let mut stat = Stat::new();
+        let mut lines: Vec<MoveLine> = board
+            .get_available_moves_for(color)
+            .map(|mv| MoveLine::new(board, color, mv, depth, params, &mut stat))
+            .collect();
+        lines.sort_by_key(|line| -line.score);
+        Tutor { lines }
+    }
+
+    // The best move, and what goes wrong with the next best one
+    pub fn hint(&self) -> Option<String> {
+        let best = self.lines.first()?;
+        let mut hint = format!("Hint: {} (score: {})", best.mv, best.score);
+        match self.lines.get(1) {
+            None => hint.push_str(", the only move"),
+            Some(next) => match reasons(next, best).first() {
+                Some(reason) => hint.push_str(&format!(". The next best, {}, {}", next.mv, reason)),
+                None if best.score == next.score => hint.push_str(&format!(", as good as {}", next.mv)),
+                None => hint.push_str(&format!(", {} ahead of {}", best.score - next.score, next.mv)),
+            },
+        }
+        Some(hint)
+    }
+
+    // A warning if `played` is a mistake or a blunder
+    pub fn check(&self, played: Pos2D, thresholds: &Thresholds) -> Option<Advice<'_>> {
+        let best = self.lines.first()?;
+        let line = self.lines.iter().find(|line| line.mv == played)?;
+        let loss = best.score - line.score;
+        let verdict = thresholds.classify(loss);
+        if verdict != Verdict::Mistake && verdict != Verdict::Blunder {
+            return None;
+        }
+        Some(Advice { verdict, loss, played: line, best, reasons: reasons(line, best) })
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod test {
+
+    use crate::board::*;
+    use crate::color::*;
+    use crate::params::*;
+    use crate::review::*;
+    use crate::tutor::*;
+
+    // Black can take c2 from b1, an X-square, after which White takes the corner a0.
+    // The other move, e5, is harmless.
+    fn get_x_square_board() -> Board {
+        let mut b = Board::new();
+        b.set_at_c('D', 3, Color::Black);
+        b.set_at_c('G', 5, Color::Black);
+        b.set_at_c('C', 2, Color::White);
+        b.set_at_c('E', 4, Color::White);
+        b.set_at_c('F', 5, Color::White);
+        b
+    }
+
+    #[test]
+    fn test_warns_about_giving_up_a_corner() {
+        let tutor = Tutor::new(&get_x_square_board(), Color::Black, 1, &EvalParams::default());
+        let advice = tutor.check(Pos2D::new(1, 1), &Thresholds::default()).unwrap();
+        assert_eq!(advice.verdict, Verdict::Blunder);
+        assert_eq!(advice.best.mv, Pos2D::new(4, 5));
+        assert_eq!(advice.played.reply, Some(Pos2D::new(0, 0)));
+        assert!(advice.reasons.iter().any(|r| r.starts_with("gives up a corner")));
+        assert!(advice.to_string().contains("Better is e5"));
+
+        assert!(tutor.check(Pos2D::new(4, 5), &Thresholds::default()).is_none());
+    }
+
+    #[test]
+    fn test_hint_explains_the_alternative() {
+        let tutor = Tutor::new(&get_x_square_board(), Color::Black, 1, &EvalParams::default());
+        let hint = tutor.hint().unwrap();
+        assert!(hint.starts_with("Hint: e5"));
+        assert!(hint.contains("The next best, b1, gives up a corner to White"));
+    }
+}